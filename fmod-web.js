@@ -11,6 +11,30 @@
 export default function (filesPathPrefix, banksToLoad) {
   // 0 = waiting for prerun, 1 = prerun complete, 2 = loaded (and ready to play sound)
   let initState = 0;
+  // The first error thrown while initializing, if any. Loading stops once there's an error.
+  let initError = null;
+
+  function failInit(e) {
+    console.error("FMOD failed to initialize:", e);
+    if (initError === null) {
+      initError = e;
+    }
+  }
+
+  // Wraps an Emscripten callback so that errors thrown from it are kept for get_loaded to throw,
+  // rather than being lost inside Emscripten.
+  function catchInitErrors(f) {
+    return function () {
+      if (initError !== null) {
+        return;
+      }
+      try {
+        return f();
+      } catch (e) {
+        failInit(e);
+      }
+    };
+  }
 
   // The actual emscripten-instantiated FMOD object.
   let FMOD;
@@ -21,69 +45,88 @@ export default function (filesPathPrefix, banksToLoad) {
   let gSystemCore;
 
   let gFmodWebBackend;
+  // Whether get_loaded has handed out gFmodWebBackend, which it only does once.
+  let backendTaken = false;
+
+  // Filenames of banks which are already on the Emscripten filesystem (whether preloaded or
+  // fetched later), so they don't need to be fetched again.
+  let filesInFs = new Set();
+  // Download progress of the banks loaded at startup, by filename, in the same order as
+  // banksToLoad.
+  let initialDownloads = new Map();
 
   // Simple error checking function for all FMOD return values. Can only be used once FMOD runtime
-  // has been initialized.
+  // has been initialized. Throws the FMOD_RESULT code along with the message, so the Rust side can
+  // tell what went wrong.
   function CHECK_RESULT(result) {
     if (result != FMOD.OK) {
       let msg = "FMOD Error: '" + FMOD.ErrorString(result) + "'";
       console.error(msg);
-      throw msg;
+      throw { code: result, message: msg };
     }
   }
 
-  function check_finite_vec2(vec, name) {
+  // An error which didn't come from FMOD, with `kind` naming the Rust AudioError variant.
+  function audioError(kind, message) {
+    return { kind: kind, message: message };
+  }
+
+  function check_finite_vec(vec, name) {
     // check not nan
-    if (isNaN(vec[0]) || isNaN(vec[1])) {
-      throw "vec2." + name + " has NaN: " + vec[0] + ", " + vec[1];
+    if (vec.some((c) => isNaN(c))) {
+      throw audioError(
+        "InvalidParameter",
+        "vector " + name + " has NaN: " + vec.join(", ")
+      );
     }
     // check not infinite
-    if (
-      vec[0] == Infinity ||
-      vec[0] == -Infinity ||
-      vec[1] == Infinity ||
-      vec[1] == -Infinity
-    ) {
-      throw "vec2." + name + " has infinite: " + vec[0] + ", " + vec[1];
+    if (vec.some((c) => c == Infinity || c == -Infinity)) {
+      throw audioError(
+        "InvalidParameter",
+        "vector " + name + " has infinite: " + vec.join(", ")
+      );
     }
   }
 
-  function build3DAttrs(position, velocity) {
-    // Our coordinates are 10 pixels to 1 meter and x runs to the right, so we need to handle that.
-    // Theoretically, we should be able to just set the core system's 3d settings, but seems like
-    // sounds are still attenuated too much that way.
-    const UP_DIR = -1; // Assuming gravity points down (+y), so up is -y
+  function fmodVector(v) {
+    let vector = FMOD.VECTOR();
+    vector.x = v[0];
+    vector.y = v[1];
+    vector.z = v[2];
+    return vector;
+  }
 
-    check_finite_vec2(position, "position");
-    check_finite_vec2(velocity, "velocity");
+  // Takes an object matching Audio3dAttributes on the Rust side, already
+  // converted to FMOD's coordinates there.
+  function build3DAttrs(attributes) {
+    check_finite_vec(attributes.position, "position");
+    check_finite_vec(attributes.velocity, "velocity");
+    check_finite_vec(attributes.forward, "forward");
+    check_finite_vec(attributes.up, "up");
 
     let attributes3d = FMOD._3D_ATTRIBUTES();
-    attributes3d.position = FMOD.VECTOR();
-    attributes3d.position.x = -position[0] / 10.0;
-    attributes3d.position.y = position[1] / 10.0;
-    attributes3d.position.z = 0.0;
-    attributes3d.velocity = FMOD.VECTOR();
-    attributes3d.velocity.x = -velocity[0] / 10.0;
-    attributes3d.velocity.y = velocity[1] / 10.0;
-    attributes3d.velocity.z = 0.0;
-    attributes3d.forward = FMOD.VECTOR();
-    attributes3d.forward.x = 0.0;
-    attributes3d.forward.y = 0.0;
-    attributes3d.forward.z = 1.0;
-    attributes3d.up = FMOD.VECTOR();
-    attributes3d.up.x = 0.0;
-    attributes3d.up.y = UP_DIR;
-    attributes3d.up.z = 0.0;
+    attributes3d.position = fmodVector(attributes.position);
+    attributes3d.velocity = fmodVector(attributes.velocity);
+    attributes3d.forward = fmodVector(attributes.forward);
+    attributes3d.up = fmodVector(attributes.up);
     return attributes3d;
   }
 
   class FmodWebBackend {
     constructor(banks) {
-      if (!banks || banks.length === 0) {
-        throw "Can't create FmodWebBackend with empty banks array";
+      // banks may be empty if they're all going to be loaded later (e.g. from memory)
+      if (!banks) {
+        throw audioError(
+          "Other",
+          "Can't create FmodWebBackend with null/undefined banks array"
+        );
       }
 
       this.banks = banks;
+      // bank name => WebAssetSource, for banks loaded via load_bank_from_source
+      this.bankSources = new Map();
+      // the listeners last set, so that one can be removed on its own
+      this.listeners = [];
     }
 
     update() {
@@ -103,40 +146,179 @@ export default function (filesPathPrefix, banksToLoad) {
     get_event_list() {
       let result = [];
 
-      for (const [bankName, bank] of this.banks) {
-        let countOut = {};
-        CHECK_RESULT(bank.getEventCount(countOut));
-        const count = countOut.val;
+      for (const bank of this.get_bank_list()) {
+        result.push(...bank.get_event_list());
+      }
 
-        if (count > 0) {
-          let arrayOut = { val: new Array(count) };
-          let retrievedCountOut = {};
+      return result;
+    }
 
-          // Call the FMOD function with proper parameters
-          CHECK_RESULT(bank.getEventList(arrayOut, count, retrievedCountOut));
-          if (retrievedCountOut.val != count) {
-            throw `FMOD event list count mismatch for bank ${bankName}`;
-          }
+    get_bank(filename) {
+      for (const bank of this.get_bank_list()) {
+        if (bank.filename == filename) {
+          return bank;
+        }
+      }
 
-          // Process each event description in the array
-          for (const eventDesc of arrayOut.val) {
-            result.push(new FmodEventDescription(eventDesc));
-          }
+      throw audioError("BankNotFound", "FMOD bank not loaded: " + filename);
+    }
+
+    get_bank_list() {
+      // forget about any banks which have since been unloaded
+      this.banks = this.banks.filter(([_bankName, bank]) => bank.isValid());
+
+      return this.banks
+        .filter(([_bankName, bank]) => {
+          let stateOut = {};
+          bank.getLoadingState(stateOut);
+          return stateOut.val == FMOD.STUDIO_LOADING_STATE_LOADED;
+        })
+        .map(([bankName, bank]) => new FmodBank(bankName, bank));
+    }
+
+    load_bank(filename) {
+      return new FmodBankLoader(this, filename);
+    }
+
+    load_bank_from_memory(name, data) {
+      // FMOD copies the data (STUDIO_LOAD_MEMORY rather than STUDIO_LOAD_MEMORY_POINT), which
+      // matters because data is a view into wasm memory that may be invalidated later.
+      let bankOut = {};
+      CHECK_RESULT(
+        gSystemStudio.loadBankMemory(
+          data,
+          data.length,
+          FMOD.STUDIO_LOAD_MEMORY,
+          FMOD.STUDIO_LOAD_BANK_NORMAL,
+          bankOut
+        )
+      );
+      this.banks.push([name, bankOut.val]);
+      return new FmodBank(name, bankOut.val);
+    }
+
+    // source is a WebAssetSource from Rust, which the file callbacks read from. We hang on to it
+    // for as long as the bank is loaded since FMOD may open streams from it at any time.
+    load_bank_from_source(name, source) {
+      let info = FMOD.STUDIO_BANK_INFO();
+      info.opencallback = function (_name, filesize, handle, _userdata) {
+        try {
+          handle.val = source.open();
+          filesize.val = source.size(handle.val);
+          return FMOD.OK;
+        } catch (e) {
+          console.error("FMOD failed to open bank source " + name + ": " + e);
+          return FMOD.ERR_FILE_NOTFOUND;
+        }
+      };
+      info.closecallback = function (handle, _userdata) {
+        source.close(handle);
+        return FMOD.OK;
+      };
+      info.readcallback = function (
+        handle,
+        buffer,
+        sizebytes,
+        bytesread,
+        _userdata
+      ) {
+        try {
+          // buffer is an address on FMOD's Emscripten heap, so copy the data there
+          let data = source.read(handle, sizebytes);
+          FMOD.HEAPU8.set(data, buffer);
+          bytesread.val = data.length;
+          return data.length < sizebytes ? FMOD.ERR_FILE_EOF : FMOD.OK;
+        } catch (e) {
+          console.error("FMOD failed to read bank source " + name + ": " + e);
+          return FMOD.ERR_FILE_BAD;
+        }
+      };
+      info.seekcallback = function (handle, pos, _userdata) {
+        try {
+          source.seek(handle, pos);
+          return FMOD.OK;
+        } catch (e) {
+          console.error("FMOD failed to seek bank source " + name + ": " + e);
+          return FMOD.ERR_FILE_COULDNOTSEEK;
         }
+      };
+
+      let bankOut = {};
+      CHECK_RESULT(
+        gSystemStudio.loadBankCustom(
+          info,
+          FMOD.STUDIO_LOAD_BANK_NORMAL,
+          bankOut
+        )
+      );
+      this.banks.push([name, bankOut.val]);
+      this.bankSources.set(name, source);
+      return new FmodBank(name, bankOut.val);
+    }
+
+    unload_bank(filename) {
+      // banks unloaded through their own handle are still in the list, and may
+      // have been loaded again under the same name since
+      let idx = this.banks.findIndex(
+        ([bankName, bank]) => bankName == filename && bank.isValid()
+      );
+      if (idx == -1) {
+        throw audioError("BankNotFound", "FMOD bank not loaded: " + filename);
+      }
+
+      let [_bankName, bank] = this.banks[idx];
+      this.banks.splice(idx, 1);
+      CHECK_RESULT(bank.unload());
+
+      // The file callbacks still hold on to the source, since FMOD may close streams after the
+      // unload call returns
+      this.bankSources.delete(filename);
+    }
+
+    get_bus(path) {
+      let busOut = {};
+      CHECK_RESULT(gSystemStudio.getBus(path, busOut));
+      return new FmodBus(busOut.val);
+    }
+
+    get_vca(path) {
+      let vcaOut = {};
+      CHECK_RESULT(gSystemStudio.getVCA(path, vcaOut));
+      return new FmodVca(vcaOut.val);
+    }
+
+    get_vca_list() {
+      let result = [];
+
+      for (const bank of this.get_bank_list()) {
+        result.push(...bank.get_vca_list());
       }
 
       return result;
     }
 
-    set_listeners(listenersIn) {
+    set_listeners_3d(listenersIn) {
+      if (listenersIn.length == 0) {
+        throw audioError("InvalidParameter", "At least one listener is needed");
+      }
       // make sure we don't exceed the max number of listeners
       let listeners = listenersIn.slice(0, FMOD.MAX_LISTENERS);
 
+      CHECK_RESULT(gSystemStudio.setNumListeners(listeners.length));
+
       for (let listenerIdx = 0; listenerIdx < listeners.length; listenerIdx++) {
         let listener = listeners[listenerIdx];
 
-        let attributes3d = build3DAttrs(listener.position, listener.velocity);
+        let attributes3d = build3DAttrs(listener.attributes);
+        // null (or undefined, from Rust's None) to attenuate from the listener's position
         let attenuationPosition = null;
+        if (listener.attenuation_position != null) {
+          check_finite_vec(
+            listener.attenuation_position,
+            "attenuation_position"
+          );
+          attenuationPosition = fmodVector(listener.attenuation_position);
+        }
         CHECK_RESULT(
           gSystemStudio.setListenerAttributes(
             listenerIdx,
@@ -149,6 +331,33 @@ export default function (filesPathPrefix, banksToLoad) {
           gSystemStudio.setListenerWeight(listenerIdx, listener.weight)
         );
       }
+      this.listeners = listeners;
+    }
+
+    set_listener_weight(index, weight) {
+      if (index >= this.listeners.length) {
+        throw audioError("InvalidParameter", "No listener " + index);
+      }
+      CHECK_RESULT(gSystemStudio.setListenerWeight(index, weight));
+      this.listeners[index].weight = weight;
+    }
+
+    remove_listener(index) {
+      if (index >= this.listeners.length) {
+        throw audioError("InvalidParameter", "No listener " + index);
+      }
+      if (this.listeners.length == 1) {
+        throw audioError("InvalidParameter", "At least one listener is needed");
+      }
+      this.set_listeners_3d(
+        this.listeners.filter((_listener, i) => i != index)
+      );
+    }
+
+    set_3d_settings(dopplerScale, distanceFactor, rolloffScale) {
+      CHECK_RESULT(
+        gSystemCore.set3DSettings(dopplerScale, distanceFactor, rolloffScale)
+      );
     }
 
     set_parameter_by_name(name, value) {
@@ -157,15 +366,476 @@ export default function (filesPathPrefix, banksToLoad) {
         gSystemStudio.setParameterByName(name, value, ignoreSeekSpeed)
       );
     }
+
+    get_global_parameters() {
+      let countOut = {};
+      CHECK_RESULT(gSystemStudio.getParameterDescriptionCount(countOut));
+      let listOut = {};
+      CHECK_RESULT(
+        gSystemStudio.getParameterDescriptionList(listOut, countOut.val, {})
+      );
+
+      return listOut.val.map((description) =>
+        convertParameterDescription(
+          description,
+          (labelIndex, labelOut, labelOutLength, retrievedOut) =>
+            gSystemStudio.getParameterLabelByID(
+              description.id,
+              labelIndex,
+              labelOut,
+              labelOutLength,
+              retrievedOut
+            )
+        )
+      );
+    }
+
+    // Returns an object matching AudioParameterId on the Rust side
+    get_parameter_id(name) {
+      let descriptionOut = {};
+      CHECK_RESULT(
+        gSystemStudio.getParameterDescriptionByName(name, descriptionOut)
+      );
+      let id = descriptionOut.val.id;
+      return { data1: id.data1, data2: id.data2 };
+    }
+
+    // Takes the parts of the ID separately so that nothing needs to be serialized on the Rust side
+    set_parameter_by_id(data1, data2, value) {
+      let ignoreSeekSpeed = false;
+      CHECK_RESULT(
+        gSystemStudio.setParameterByID(
+          buildParameterId(data1, data2),
+          value,
+          ignoreSeekSpeed
+        )
+      );
+    }
+  }
+
+  function buildParameterId(data1, data2) {
+    let parameterId = FMOD.STUDIO_PARAMETER_ID();
+    parameterId.data1 = data1;
+    parameterId.data2 = data2;
+    return parameterId;
+  }
+
+  // Converts an FMOD parameter description into an object matching AudioParameterDescription on
+  // the Rust side. getLabel takes the same arguments as FMOD's getParameterLabelBy* functions
+  // minus the first, since event and global parameters look labels up differently.
+  function convertParameterDescription(description, getLabel) {
+    let flags = {
+      read_only: (description.flags & FMOD.STUDIO_PARAMETER_READONLY) != 0,
+      automatic: (description.flags & FMOD.STUDIO_PARAMETER_AUTOMATIC) != 0,
+      global: (description.flags & FMOD.STUDIO_PARAMETER_GLOBAL) != 0,
+      discrete: (description.flags & FMOD.STUDIO_PARAMETER_DISCRETE) != 0,
+      labeled: (description.flags & FMOD.STUDIO_PARAMETER_LABELED) != 0,
+    };
+
+    let labels = [];
+    if (flags.labeled) {
+      // labeled parameters have one label per integer value in their range
+      let labelCount =
+        Math.round(description.maximum - description.minimum) + 1;
+      for (let labelIndex = 0; labelIndex < labelCount; labelIndex++) {
+        let labelOut = {};
+        let labelOutLength = 256;
+        let retrievedOut = {};
+        CHECK_RESULT(
+          getLabel(labelIndex, labelOut, labelOutLength, retrievedOut)
+        );
+        labels.push(labelOut.val);
+      }
+    }
+
+    return {
+      name: description.name,
+      id: { data1: description.id.data1, data2: description.id.data2 },
+      minimum: description.minimum,
+      maximum: description.maximum,
+      default_value: description.defaultvalue,
+      kind: parameterKindName(description.type),
+      flags: flags,
+      labels: labels,
+    };
+  }
+
+  function parameterKindName(kind) {
+    if (kind == FMOD.STUDIO_PARAMETER_GAME_CONTROLLED) {
+      return "GameControlled";
+    } else if (kind == FMOD.STUDIO_PARAMETER_AUTOMATIC_DISTANCE) {
+      return "AutomaticDistance";
+    } else if (kind == FMOD.STUDIO_PARAMETER_AUTOMATIC_EVENT_CONE_ANGLE) {
+      return "AutomaticEventConeAngle";
+    } else if (kind == FMOD.STUDIO_PARAMETER_AUTOMATIC_EVENT_ORIENTATION) {
+      return "AutomaticEventOrientation";
+    } else if (kind == FMOD.STUDIO_PARAMETER_AUTOMATIC_DIRECTION) {
+      return "AutomaticDirection";
+    } else if (kind == FMOD.STUDIO_PARAMETER_AUTOMATIC_ELEVATION) {
+      return "AutomaticElevation";
+    } else if (kind == FMOD.STUDIO_PARAMETER_AUTOMATIC_LISTENER_ORIENTATION) {
+      return "AutomaticListenerOrientation";
+    } else if (kind == FMOD.STUDIO_PARAMETER_AUTOMATIC_SPEED) {
+      return "AutomaticSpeed";
+    } else if (kind == FMOD.STUDIO_PARAMETER_AUTOMATIC_SPEED_ABSOLUTE) {
+      return "AutomaticSpeedAbsolute";
+    } else if (kind == FMOD.STUDIO_PARAMETER_AUTOMATIC_DISTANCE_NORMALIZED) {
+      return "AutomaticDistanceNormalized";
+    } else {
+      throw audioError("Other", "Unknown FMOD parameter type: " + kind);
+    }
+  }
+
+  // Converts an FMOD event callback into a value matching AudioEventCallback on the Rust side, or
+  // null for callback types that aren't surfaced there.
+  function convertEventCallback(type, parameters) {
+    if (type == FMOD.STUDIO_EVENT_CALLBACK_STARTING) {
+      return "Starting";
+    } else if (type == FMOD.STUDIO_EVENT_CALLBACK_STARTED) {
+      return "Started";
+    } else if (type == FMOD.STUDIO_EVENT_CALLBACK_RESTARTED) {
+      return "Restarted";
+    } else if (type == FMOD.STUDIO_EVENT_CALLBACK_STOPPED) {
+      return "Stopped";
+    } else if (type == FMOD.STUDIO_EVENT_CALLBACK_START_FAILED) {
+      return "StartFailed";
+    } else if (type == FMOD.STUDIO_EVENT_CALLBACK_TIMELINE_MARKER) {
+      return {
+        TimelineMarker: {
+          name: parameters.name,
+          position: parameters.position,
+        },
+      };
+    } else if (type == FMOD.STUDIO_EVENT_CALLBACK_TIMELINE_BEAT) {
+      return {
+        TimelineBeat: {
+          bar: parameters.bar,
+          beat: parameters.beat,
+          position: parameters.position,
+          tempo: parameters.tempo,
+          time_signature_upper: parameters.timesignatureupper,
+          time_signature_lower: parameters.timesignaturelower,
+        },
+      };
+    } else if (type == FMOD.STUDIO_EVENT_CALLBACK_SOUND_PLAYED) {
+      return "SoundPlayed";
+    } else if (type == FMOD.STUDIO_EVENT_CALLBACK_SOUND_STOPPED) {
+      return "SoundStopped";
+    } else if (type == FMOD.STUDIO_EVENT_CALLBACK_REAL_TO_VIRTUAL) {
+      return "RealToVirtual";
+    } else if (type == FMOD.STUDIO_EVENT_CALLBACK_VIRTUAL_TO_REAL) {
+      return "VirtualToReal";
+    } else {
+      return null;
+    }
+  }
+
+  function stopModeValue(mode) {
+    if (mode == "AllowFadeout") {
+      return FMOD.STUDIO_STOP_ALLOWFADEOUT;
+    } else if (mode == "Immediate") {
+      return FMOD.STUDIO_STOP_IMMEDIATE;
+    } else {
+      throw audioError("Other", "Unknown stop mode: " + mode);
+    }
+  }
+
+  function formatGuid(guid) {
+    function hex(value, digits) {
+      return value.toString(16).padStart(digits, "0");
+    }
+
+    let data4 = Array.from(guid.Data4, (b) => hex(b, 2));
+    return (
+      "{" +
+      hex(guid.Data1, 8) +
+      "-" +
+      hex(guid.Data2, 4) +
+      "-" +
+      hex(guid.Data3, 4) +
+      "-" +
+      data4.slice(0, 2).join("") +
+      "-" +
+      data4.slice(2).join("") +
+      "}"
+    );
+  }
+
+  function loadingStateName(state) {
+    if (state == FMOD.STUDIO_LOADING_STATE_UNLOADING) {
+      return "Unloading";
+    } else if (state == FMOD.STUDIO_LOADING_STATE_UNLOADED) {
+      return "Unloaded";
+    } else if (state == FMOD.STUDIO_LOADING_STATE_LOADING) {
+      return "Loading";
+    } else if (state == FMOD.STUDIO_LOADING_STATE_LOADED) {
+      return "Loaded";
+    } else if (state == FMOD.STUDIO_LOADING_STATE_ERROR) {
+      return "Error";
+    } else {
+      throw audioError("Other", "Unknown FMOD loading state: " + state);
+    }
+  }
+
+  // Fetches a bank over HTTP onto the Emscripten filesystem, updating `download.bytesLoaded` and
+  // `download.bytesTotal` as it goes.
+  async function fetchBankIntoFs(filename, download) {
+    let response = await fetch(filesPathPrefix + filename);
+    if (!response.ok) {
+      throw audioError(
+        response.status == 404 ? "BankNotFound" : "Other",
+        `HTTP ${response.status} fetching bank ${filename}`
+      );
+    }
+    download.bytesTotal = Number(response.headers.get("Content-Length")) || 0;
+
+    let chunks = [];
+    let reader = response.body.getReader();
+    while (true) {
+      const { done, value } = await reader.read();
+      if (done) {
+        break;
+      }
+      chunks.push(value);
+      download.bytesLoaded += value.length;
+    }
+    // Content-Length may be the compressed size, so use what we actually got
+    download.bytesTotal = download.bytesLoaded;
+
+    let data = new Uint8Array(download.bytesLoaded);
+    let offset = 0;
+    for (const chunk of chunks) {
+      data.set(chunk, offset);
+      offset += chunk.length;
+    }
+
+    let canRead = true;
+    let canWrite = false;
+    FMOD.FS_createDataFile("/", filename, data, canRead, canWrite);
+    filesInFs.add(filename);
+  }
+
+  // Loads a bank after FMOD has been initialized, fetching it over HTTP onto the Emscripten
+  // filesystem first if it isn't there already.
+  class FmodBankLoader {
+    constructor(backend, filename) {
+      this.backend = backend;
+      this.filename = filename;
+      this.download = { bytesLoaded: 0, bytesTotal: 0 };
+      this.bank = null;
+      this.error = null;
+
+      this.load().catch((e) => {
+        console.error(
+          "FMOD failed to load bank " + filename + ": " + (e.message || e)
+        );
+        this.error = e;
+      });
+    }
+
+    async load() {
+      if (!filesInFs.has(this.filename)) {
+        await fetchBankIntoFs(this.filename, this.download);
+      }
+
+      let bankOut = {};
+      CHECK_RESULT(
+        gSystemStudio.loadBankFile(
+          "/" + this.filename,
+          FMOD.STUDIO_LOAD_BANK_NONBLOCKING,
+          bankOut
+        )
+      );
+      this.bank = bankOut.val;
+      this.backend.banks.push([this.filename, this.bank]);
+    }
+
+    // Failed loads have finished too, so they report 1 rather than stalling;
+    // get_loaded throws the error.
+    get_progress() {
+      if (this.error) {
+        return 1.0;
+      }
+      if (this.bank) {
+        let stateOut = {};
+        this.bank.getLoadingState(stateOut);
+        if (stateOut.val != FMOD.STUDIO_LOADING_STATE_LOADING) {
+          return 1.0;
+        }
+      }
+      let { bytesLoaded, bytesTotal } = this.download;
+      if (bytesTotal > 0) {
+        // Content-Length may be the compressed size, so don't overshoot
+        return Math.min(bytesLoaded / bytesTotal, 1.0);
+      }
+      return 0.0;
+    }
+
+    // Returns null while still loading.
+    get_loaded() {
+      if (this.error) {
+        throw this.error;
+      }
+      if (!this.bank) {
+        return null;
+      }
+
+      let stateOut = {};
+      let result = this.bank.getLoadingState(stateOut);
+      if (stateOut.val == FMOD.STUDIO_LOADING_STATE_LOADED) {
+        return new FmodBank(this.filename, this.bank);
+      } else if (stateOut.val == FMOD.STUDIO_LOADING_STATE_LOADING) {
+        return null;
+      }
+
+      // failed asynchronous loads still need to be unloaded to release the bank handle
+      this.bank.unload();
+      // thrown the same way as CHECK_RESULT, so that the Rust side gets the FMOD_RESULT
+      this.error = {
+        code: result,
+        message:
+          "FMOD failed to load bank " +
+          this.filename +
+          ": '" +
+          FMOD.ErrorString(result) +
+          "'",
+      };
+      throw this.error;
+    }
+  }
+
+  // Wrapper class for FMOD Bank
+  class FmodBank {
+    constructor(filename, bank) {
+      if (!bank) {
+        throw audioError(
+          "Other",
+          "Can't create FmodBank with null/undefined bank: " + bank
+        );
+      }
+      this.filename = filename;
+      this.bank = bank;
+    }
+
+    get_filename() {
+      return this.filename;
+    }
+
+    get_path() {
+      let pathOut = {};
+      let pathOutLength = 4096; // probably doesn't matter?
+      let retrievedOut = {};
+      CHECK_RESULT(this.bank.getPath(pathOut, pathOutLength, retrievedOut));
+      if (retrievedOut.val == pathOutLength) {
+        throw audioError(
+          "Other",
+          "FMOD bank path name too long: " + pathOut.val
+        );
+      }
+
+      return pathOut.val;
+    }
+
+    get_id() {
+      let idOut = {};
+      CHECK_RESULT(this.bank.getID(idOut));
+      return formatGuid(idOut.val);
+    }
+
+    get_loading_state() {
+      let stateOut = {};
+      let result = this.bank.getLoadingState(stateOut);
+      // a failed load reports the load's error code along with the error state
+      if (stateOut.val != FMOD.STUDIO_LOADING_STATE_ERROR) {
+        CHECK_RESULT(result);
+      }
+      return loadingStateName(stateOut.val);
+    }
+
+    get_sample_loading_state() {
+      let stateOut = {};
+      CHECK_RESULT(this.bank.getSampleLoadingState(stateOut));
+      return loadingStateName(stateOut.val);
+    }
+
+    load_sample_data() {
+      CHECK_RESULT(this.bank.loadSampleData());
+    }
+
+    unload_sample_data() {
+      CHECK_RESULT(this.bank.unloadSampleData());
+    }
+
+    get_event_list() {
+      let result = [];
+
+      let countOut = {};
+      CHECK_RESULT(this.bank.getEventCount(countOut));
+      const count = countOut.val;
+
+      if (count > 0) {
+        let arrayOut = { val: new Array(count) };
+        let retrievedCountOut = {};
+
+        // Call the FMOD function with proper parameters
+        CHECK_RESULT(
+          this.bank.getEventList(arrayOut, count, retrievedCountOut)
+        );
+        if (retrievedCountOut.val != count) {
+          throw audioError(
+            "Other",
+            `FMOD event list count mismatch for bank ${this.filename}`
+          );
+        }
+
+        // Process each event description in the array
+        for (const eventDesc of arrayOut.val) {
+          result.push(new FmodEventDescription(eventDesc));
+        }
+      }
+
+      return result;
+    }
+
+    get_vca_list() {
+      let result = [];
+
+      let countOut = {};
+      CHECK_RESULT(this.bank.getVCACount(countOut));
+      const count = countOut.val;
+
+      if (count > 0) {
+        let arrayOut = { val: new Array(count) };
+        let retrievedCountOut = {};
+        CHECK_RESULT(this.bank.getVCAList(arrayOut, count, retrievedCountOut));
+        if (retrievedCountOut.val != count) {
+          throw audioError(
+            "Other",
+            `FMOD VCA list count mismatch for bank ${this.filename}`
+          );
+        }
+
+        for (const vca of arrayOut.val) {
+          result.push(new FmodVca(vca));
+        }
+      }
+
+      return result;
+    }
+
+    unload() {
+      CHECK_RESULT(this.bank.unload());
+    }
   }
 
   // Wrapper class for FMOD Event Description
   class FmodEventDescription {
     constructor(eventDescription) {
       if (!eventDescription) {
-        throw (
+        throw audioError(
+          "Other",
           "Can't create FmodEventDescription with null/undefined eventDescription: " +
-          eventDescription
+            eventDescription
         );
       }
       this.eventDescription = eventDescription;
@@ -185,7 +855,10 @@ export default function (filesPathPrefix, banksToLoad) {
         this.eventDescription.getPath(pathOut, pathOutLength, retrievedOut)
       );
       if (retrievedOut.val == pathOutLength) {
-        throw "FMOD event path name too long: " + pathOut.val;
+        throw audioError(
+          "Other",
+          "FMOD event path name too long: " + pathOut.val
+        );
       }
 
       return pathOut.val;
@@ -194,15 +867,207 @@ export default function (filesPathPrefix, banksToLoad) {
     load_sample_data() {
       CHECK_RESULT(this.eventDescription.loadSampleData());
     }
+
+    is_snapshot() {
+      let snapshotOut = {};
+      CHECK_RESULT(this.eventDescription.isSnapshot(snapshotOut));
+      return snapshotOut.val;
+    }
+
+    get_parameter_description_count() {
+      let countOut = {};
+      CHECK_RESULT(
+        this.eventDescription.getParameterDescriptionCount(countOut)
+      );
+      return countOut.val;
+    }
+
+    // Returns an object matching AudioParameterDescription on the Rust side
+    get_parameter_description_by_index(index) {
+      let descriptionOut = {};
+      CHECK_RESULT(
+        this.eventDescription.getParameterDescriptionByIndex(
+          index,
+          descriptionOut
+        )
+      );
+      return convertParameterDescription(
+        descriptionOut.val,
+        (labelIndex, labelOut, labelOutLength, retrievedOut) =>
+          this.eventDescription.getParameterLabelByIndex(
+            index,
+            labelIndex,
+            labelOut,
+            labelOutLength,
+            retrievedOut
+          )
+      );
+    }
+  }
+
+  // Wrapper class for FMOD Bus
+  class FmodBus {
+    constructor(bus) {
+      if (!bus) {
+        throw audioError(
+          "Other",
+          "Can't create FmodBus with null/undefined bus: " + bus
+        );
+      }
+      this.bus = bus;
+    }
+
+    get_path() {
+      let pathOut = {};
+      let pathOutLength = 4096; // probably doesn't matter?
+      let retrievedOut = {};
+      CHECK_RESULT(this.bus.getPath(pathOut, pathOutLength, retrievedOut));
+      if (retrievedOut.val == pathOutLength) {
+        throw audioError(
+          "Other",
+          "FMOD bus path name too long: " + pathOut.val
+        );
+      }
+
+      return pathOut.val;
+    }
+
+    set_volume(volume) {
+      CHECK_RESULT(this.bus.setVolume(volume));
+    }
+
+    get_volume() {
+      let volumeOut = {};
+      let finalVolumeOut = {};
+      CHECK_RESULT(this.bus.getVolume(volumeOut, finalVolumeOut));
+      return { volume: volumeOut.val, final_volume: finalVolumeOut.val };
+    }
+
+    set_mute(mute) {
+      CHECK_RESULT(this.bus.setMute(mute));
+    }
+
+    get_mute() {
+      let muteOut = {};
+      CHECK_RESULT(this.bus.getMute(muteOut));
+      return muteOut.val;
+    }
+
+    set_paused(paused) {
+      CHECK_RESULT(this.bus.setPaused(paused));
+    }
+
+    get_paused() {
+      let pausedOut = {};
+      CHECK_RESULT(this.bus.getPaused(pausedOut));
+      return pausedOut.val;
+    }
+
+    // mode is an AudioStopMode variant name from Rust
+    stop_all_events(mode) {
+      CHECK_RESULT(this.bus.stopAllEvents(stopModeValue(mode)));
+    }
+
+    enable_metering() {
+      CHECK_RESULT(this.bus.lockChannelGroup());
+      // the channel group gets created asynchronously, so if it isn't there yet, metering on its
+      // DSP is enabled once it turns up in get_metering
+      let dsp = this.get_head_dsp();
+      if (dsp != null) {
+        CHECK_RESULT(dsp.setMeteringEnabled(false, true));
+      }
+    }
+
+    // The DSP the bus's output levels are metered on, or null if its channel group hasn't been
+    // created yet
+    get_head_dsp() {
+      let channelGroupOut = {};
+      let result = this.bus.getChannelGroup(channelGroupOut);
+      if (result == FMOD.ERR_STUDIO_NOT_LOADED) {
+        return null;
+      }
+      CHECK_RESULT(result);
+
+      let dspOut = {};
+      CHECK_RESULT(
+        channelGroupOut.val.getDSP(FMOD.CHANNELCONTROL_DSP_HEAD, dspOut)
+      );
+      return dspOut.val;
+    }
+
+    // Returns an object matching AudioMeteringInfo on the Rust side, or null if the bus's channel
+    // group hasn't been created yet or nothing has been metered since
+    get_metering() {
+      let dsp = this.get_head_dsp();
+      if (dsp == null) {
+        return null;
+      }
+      let inputEnabledOut = {};
+      let outputEnabledOut = {};
+      CHECK_RESULT(dsp.getMeteringEnabled(inputEnabledOut, outputEnabledOut));
+      if (!outputEnabledOut.val) {
+        // nothing has been measured yet, so the levels would all read zero
+        CHECK_RESULT(dsp.setMeteringEnabled(false, true));
+        return null;
+      }
+
+      let outputOut = {};
+      CHECK_RESULT(dsp.getMeteringInfo(null, outputOut));
+      let output = outputOut.val;
+      return {
+        peak_level: Array.from(output.peaklevel.slice(0, output.numchannels)),
+        rms_level: Array.from(output.rmslevel.slice(0, output.numchannels)),
+      };
+    }
+  }
+
+  // Wrapper class for FMOD VCA
+  class FmodVca {
+    constructor(vca) {
+      if (!vca) {
+        throw audioError(
+          "Other",
+          "Can't create FmodVca with null/undefined vca: " + vca
+        );
+      }
+      this.vca = vca;
+    }
+
+    get_path() {
+      let pathOut = {};
+      let pathOutLength = 4096; // probably doesn't matter?
+      let retrievedOut = {};
+      CHECK_RESULT(this.vca.getPath(pathOut, pathOutLength, retrievedOut));
+      if (retrievedOut.val == pathOutLength) {
+        throw audioError(
+          "Other",
+          "FMOD VCA path name too long: " + pathOut.val
+        );
+      }
+
+      return pathOut.val;
+    }
+
+    set_volume(volume) {
+      CHECK_RESULT(this.vca.setVolume(volume));
+    }
+
+    get_volume() {
+      let volumeOut = {};
+      let finalVolumeOut = {};
+      CHECK_RESULT(this.vca.getVolume(volumeOut, finalVolumeOut));
+      return { volume: volumeOut.val, final_volume: finalVolumeOut.val };
+    }
   }
 
   // Wrapper class for FMOD Event Instance
   class FmodEventInstance {
     constructor(instance) {
       if (!instance) {
-        throw (
+        throw audioError(
+          "Other",
           "Can't create FmodEventInstance with null/undefined instance: " +
-          instance
+            instance
         );
       }
       this.instance = instance;
@@ -216,12 +1081,13 @@ export default function (filesPathPrefix, banksToLoad) {
       CHECK_RESULT(this.instance.start());
     }
 
-    stop() {
-      CHECK_RESULT(this.instance.stop(FMOD.STUDIO_STOP_IMMEDIATE));
+    // mode is an AudioStopMode variant name from Rust
+    stop(mode) {
+      CHECK_RESULT(this.instance.stop(stopModeValue(mode)));
     }
 
-    set_3d_attributes(position, velocity) {
-      let attributes3d = build3DAttrs(position, velocity);
+    set_attributes_3d(attributes) {
+      let attributes3d = build3DAttrs(attributes);
       CHECK_RESULT(this.instance.set3DAttributes(attributes3d));
     }
 
@@ -240,34 +1106,108 @@ export default function (filesPathPrefix, banksToLoad) {
       } else if (stateOut.val == FMOD.STUDIO_PLAYBACK_STOPPING) {
         return "Stopping";
       } else {
-        throw "Unknown FMOD playback state: " + stateOut.val;
+        throw audioError(
+          "Other",
+          "Unknown FMOD playback state: " + stateOut.val
+        );
       }
     }
+
+    set_parameter_by_name(name, value) {
+      let ignoreSeekSpeed = false;
+      CHECK_RESULT(
+        this.instance.setParameterByName(name, value, ignoreSeekSpeed)
+      );
+    }
+
+    set_parameter_by_id(data1, data2, value) {
+      let ignoreSeekSpeed = false;
+      CHECK_RESULT(
+        this.instance.setParameterByID(
+          buildParameterId(data1, data2),
+          value,
+          ignoreSeekSpeed
+        )
+      );
+    }
+
+    set_parameter_by_name_with_label(name, label) {
+      let ignoreSeekSpeed = false;
+      CHECK_RESULT(
+        this.instance.setParameterByNameWithLabel(name, label, ignoreSeekSpeed)
+      );
+    }
+
+    get_parameter_by_name(name) {
+      let valueOut = {};
+      let finalValueOut = {};
+      CHECK_RESULT(
+        this.instance.getParameterByName(name, valueOut, finalValueOut)
+      );
+      return { value: valueOut.val, final_value: finalValueOut.val };
+    }
+
+    enable_callbacks() {
+      this.callbacks = [];
+      let mask =
+        FMOD.STUDIO_EVENT_CALLBACK_STARTING |
+        FMOD.STUDIO_EVENT_CALLBACK_STARTED |
+        FMOD.STUDIO_EVENT_CALLBACK_RESTARTED |
+        FMOD.STUDIO_EVENT_CALLBACK_STOPPED |
+        FMOD.STUDIO_EVENT_CALLBACK_START_FAILED |
+        FMOD.STUDIO_EVENT_CALLBACK_TIMELINE_MARKER |
+        FMOD.STUDIO_EVENT_CALLBACK_TIMELINE_BEAT |
+        FMOD.STUDIO_EVENT_CALLBACK_SOUND_PLAYED |
+        FMOD.STUDIO_EVENT_CALLBACK_SOUND_STOPPED |
+        FMOD.STUDIO_EVENT_CALLBACK_REAL_TO_VIRTUAL |
+        FMOD.STUDIO_EVENT_CALLBACK_VIRTUAL_TO_REAL;
+      CHECK_RESULT(
+        this.instance.setCallback((type, _event, parameters) => {
+          let callback = convertEventCallback(type, parameters);
+          if (callback !== null) {
+            this.callbacks.push(callback);
+          }
+          return FMOD.OK;
+        }, mask)
+      );
+    }
+
+    // Returns an array of values matching AudioEventCallback on the Rust side
+    poll_callbacks() {
+      if (this.callbacks === undefined) {
+        throw audioError(
+          "Other",
+          "Callbacks haven't been enabled for this event instance"
+        );
+      }
+      let callbacks = this.callbacks;
+      this.callbacks = [];
+      return callbacks;
+    }
   }
 
   // The FMOD object is a global object that is used to interact with the FMOD API; Emscripten
   // will populate this object with the FMOD API when FMODModule is called with it.
   FMOD = {
     // runs before emscripten runtime is initialized
-    preRun: function () {
-      let folderName = "/";
-      let canRead = true;
-      let canWrite = false;
-
+    preRun: catchInitErrors(function () {
+      // Fetch the banks ourselves rather than with FS_createPreloadedFile so that we can report
+      // download progress. The run dependencies hold off onRuntimeInitialized until they're done.
       for (const fileToLoad of banksToLoad) {
-        FMOD.FS_createPreloadedFile(
-          folderName,
-          fileToLoad,
-          filesPathPrefix + fileToLoad,
-          canRead,
-          canWrite
-        );
+        let download = { bytesLoaded: 0, bytesTotal: 0 };
+        initialDownloads.set(fileToLoad, download);
+
+        let dependency = "bank:" + fileToLoad;
+        FMOD.addRunDependency(dependency);
+        fetchBankIntoFs(fileToLoad, download)
+          .then(() => FMOD.removeRunDependency(dependency))
+          .catch(failInit);
       }
 
       initState = 1;
-    },
+    }),
     // runs after emscripten runtime is initialized and fmod is loaded
-    onRuntimeInitialized: function () {
+    onRuntimeInitialized: catchInitErrors(function () {
       // A temporary empty object to hold return values
       let outval = {};
 
@@ -328,8 +1268,11 @@ export default function (filesPathPrefix, banksToLoad) {
         loadedBanks.push([bankToLoad, bankOut.val]);
       }
 
-      if (!masterBankFound) {
-        throw "Master bank not found: at least one bank filename must be 'Master.bank'";
+      if (banksToLoad.length > 0 && !masterBankFound) {
+        throw audioError(
+          "InvalidParameter",
+          "Master bank not found: at least one bank filename must be 'Master.bank'"
+        );
       }
 
       console.log("FMOD loading complete");
@@ -338,6 +1281,10 @@ export default function (filesPathPrefix, banksToLoad) {
       gFmodWebBackend = new FmodWebBackend(loadedBanks);
 
       return FMOD.OK;
+    }),
+    // runs if Emscripten gives up, e.g. because the FMOD wasm couldn't be fetched
+    onAbort: function (what) {
+      failInit(audioError("Other", "FMOD aborted: " + what));
     },
   };
 
@@ -345,27 +1292,57 @@ export default function (filesPathPrefix, banksToLoad) {
   // We use Rust naming conventions for function names since we expect to expose this directly
   // to Rust via wasm-bindgen.
   let fmodLoader = {
+    // Returns "Pending", "Ready" or "Failed", matching LoadState on the Rust side
+    get_state: function () {
+      if (initError !== null) {
+        return "Failed";
+      } else if (initState == 2) {
+        return "Ready";
+      } else {
+        return "Pending";
+      }
+    },
+
+    // Returns an array of values matching BankDownloadProgress on the Rust side
+    get_download_progress: function () {
+      return Array.from(initialDownloads, ([filename, download]) => ({
+        filename: filename,
+        bytes_downloaded: download.bytesLoaded,
+        bytes_total: download.bytesTotal,
+      }));
+    },
+
+    // The error which stopped initialization, or null
+    get_error: function () {
+      return initError;
+    },
+
+    // Returns the backend once it's ready, or null while it's still loading. Throws the error if
+    // initialization failed, or if the backend has already been returned.
     get_loaded: function () {
-      //NB: we haven't got any particular detection of error states here - so if audio loading is
-      //failing completely, we'll just hang in whatever state we were in when the error occurred.
-      //(Ideally we'd save the latest error and throw it here again so it can be 'caught' as a Rust
-      // error type)
-      if (initState == 0) {
-        throw "Waiting for prerun";
-      } else if (initState == 1) {
-        throw "Setting up fmod";
+      if (initError !== null) {
+        throw initError;
+      } else if (backendTaken) {
+        throw audioError(
+          "Other",
+          "The audio backend has already been taken from its loader"
+        );
       } else if (initState == 2) {
+        backendTaken = true;
         return gFmodWebBackend;
       } else {
-        // this is a bug: we should have enumerated all known steps of the loading process above.
-        throw "Unknown FMOD loading state";
+        return null;
       }
     },
   };
 
   // begin initializing the fmod controller right away: get Emscripten to load the FMOD API
   // so that Emscripten will call our FMOD object method callbacks
-  FMODModule(FMOD);
+  try {
+    FMODModule(FMOD);
+  } catch (e) {
+    failInit(e);
+  }
 
   return fmodLoader;
 }
@@ -1,51 +1,230 @@
-use std::ffi::{c_char, c_int};
+use std::cell::RefCell;
+use std::ffi::{c_char, c_int, c_uint, c_void};
+use std::sync::Mutex;
 
 use fmod::{Utf8CStr, Utf8CString};
 
+use super::error::*;
 use super::*;
 
 pub fn load_audio_backend(
     banks_path: &str,
     bank_filenames: &[&str],
+    space: AudioSpaceConfig,
 ) -> Box<dyn AudioBackendLoader> {
+    let bank_filenames = bank_filenames
+        .into_iter()
+        .map(|s| s.to_string())
+        .collect::<Vec<_>>();
+    // tests run without a sound card
+    let output = if cfg!(test) {
+        fmod::OutputType::NoSound
+    } else {
+        fmod::OutputType::AutoDetect
+    };
+    let state =
+        match FmodOxideAudioBackend::start_loading(&bank_filenames, banks_path, output, space) {
+            Ok((backend, banks)) => LoaderState::Loading { backend, banks },
+            Err(init_err) => {
+                error!("Error initializing fmod_oxide audio backend: {init_err}");
+                LoaderState::Failed(init_err)
+            }
+        };
     Box::new(FmodOxideAudioBackendLoader {
         banks_path: banks_path.to_owned(),
-        bank_filenames: bank_filenames
-            .into_iter()
-            .map(|s| s.to_string())
-            .collect::<Vec<_>>(),
+        bank_filenames,
+        state: RefCell::new(state),
     })
 }
 
+impl From<fmod::Error> for AudioError {
+    fn from(e: fmod::Error) -> Self {
+        let message = e.to_string();
+        AudioError::from_fmod_code(fmod_sys::FMOD_RESULT::from(e).0 as FmodResultCode, message)
+    }
+}
+
+/// FMOD is initialized as soon as loading starts, and the banks are then loaded in the
+/// background; each call to [AudioBackendLoader::get_state] or [AudioBackendLoader::get_loaded]
+/// updates FMOD and checks on them.
 struct FmodOxideAudioBackendLoader {
     banks_path: String,
     bank_filenames: Vec<String>,
+    state: RefCell<LoaderState>,
 }
 
-impl AudioBackendLoader for FmodOxideAudioBackendLoader {
-    fn get_loaded(&self) -> Option<AudioResult<Box<dyn AudioBackend>>> {
-        // we just load the backend synchronously here but obviously this could be done
-        // asynchronously in the future
-        match FmodOxideAudioBackend::init(&self.bank_filenames, &self.banks_path) {
-            Ok(backend) => {
+enum LoaderState {
+    Loading {
+        backend: Box<FmodOxideAudioBackend>,
+        banks: Vec<FmodOxideBank>,
+    },
+    Ready(Box<FmodOxideAudioBackend>),
+    /// The backend has been returned by [AudioBackendLoader::get_loaded].
+    Taken,
+    Failed(AudioError),
+}
+
+impl FmodOxideAudioBackendLoader {
+    /// Move on to [LoaderState::Ready] or [LoaderState::Failed] once the banks have finished
+    /// loading.
+    fn poll(&self) {
+        let mut state = self.state.borrow_mut();
+        let LoaderState::Loading { backend, banks } = &*state else {
+            return;
+        };
+        let loaded = poll_initial_banks(backend, banks);
+        if let Ok(false) = loaded {
+            return;
+        }
+
+        let LoaderState::Loading { backend, .. } =
+            std::mem::replace(&mut *state, LoaderState::Taken)
+        else {
+            unreachable!("checked above");
+        };
+        *state = match loaded {
+            Ok(_) => {
                 info!("FMOD Oxide audio backend initialized");
-                Some(Ok(backend))
+                LoaderState::Ready(backend)
             }
             Err(init_err) => {
                 error!("Error initializing fmod_oxide audio backend: {init_err}");
-                Some(Err(init_err.into()))
+                let _ = backend.shutdown();
+                LoaderState::Failed(init_err)
+            }
+        };
+    }
+}
+
+/// Returns whether all of the banks have loaded, erroring if any of them failed to.
+fn poll_initial_banks(
+    backend: &FmodOxideAudioBackend,
+    banks: &[FmodOxideBank],
+) -> AudioResult<bool> {
+    // commands (including loading banks) are only sent to FMOD's async thread on update
+    backend.system.update()?;
+    for bank in banks {
+        match bank.bank.get_loading_state()? {
+            fmod::studio::LoadingState::Loaded => {}
+            fmod::studio::LoadingState::Loading => return Ok(false),
+            fmod::studio::LoadingState::Error(e) => {
+                return Err(AudioError::from(e).context(format!("Loading bank: {}", bank.filename)));
+            }
+            state => {
+                return Err(AudioError::other(format!(
+                    "Bank {} unexpectedly in state {state:?} while loading",
+                    bank.filename
+                )));
             }
         }
     }
+    // the web backend preloads the master bank's samples, so do the same here
+    if let Some(master) = banks.iter().find(|b| b.filename == "Master.bank") {
+        master
+            .bank
+            .load_sample_data()
+            .context("Loading master bank sample data")?;
+    }
+    Ok(true)
+}
+
+impl AudioBackendLoader for FmodOxideAudioBackendLoader {
+    fn get_state(&self) -> LoadState {
+        self.poll();
+        match &*self.state.borrow() {
+            LoaderState::Loading { banks, .. } => {
+                let loaded = banks
+                    .iter()
+                    .filter(|b| {
+                        matches!(
+                            b.bank.get_loading_state(),
+                            Ok(fmod::studio::LoadingState::Loaded)
+                        )
+                    })
+                    .count();
+                LoadState::Pending(loaded as f32 / banks.len() as f32)
+            }
+            LoaderState::Ready(_) | LoaderState::Taken => LoadState::Ready,
+            LoaderState::Failed(e) => LoadState::Failed(e.clone()),
+        }
+    }
+
+    fn get_progress(&self) -> LoadProgress {
+        // banks are read straight from disk, so there's nothing to download
+        let banks = self
+            .bank_filenames
+            .iter()
+            .map(|filename| {
+                let path = std::path::Path::new(&self.banks_path).join(filename);
+                let size = std::fs::metadata(path).map_or(0, |m| m.len());
+                BankDownloadProgress {
+                    filename: filename.clone(),
+                    bytes_downloaded: size,
+                    bytes_total: size,
+                }
+            })
+            .collect();
+        LoadProgress { banks }
+    }
+
+    fn get_loaded(&self) -> Option<AudioResult<Box<dyn AudioBackend>>> {
+        self.poll();
+        let mut state = self.state.borrow_mut();
+        match &*state {
+            LoaderState::Loading { .. } => None,
+            LoaderState::Ready(_) => {
+                let LoaderState::Ready(backend) =
+                    std::mem::replace(&mut *state, LoaderState::Taken)
+                else {
+                    unreachable!("checked above");
+                };
+                Some(Ok(backend))
+            }
+            LoaderState::Taken => Some(Err(AudioError::other(
+                "The audio backend has already been taken from its loader",
+            ))),
+            LoaderState::Failed(e) => Some(Err(e.clone())),
+        }
+    }
 }
 
 struct FmodOxideAudioBackend {
     system: fmod::studio::System,
-    banks: Vec<(String, fmod::studio::Bank)>,
+    banks_path: std::path::PathBuf,
+    /// All banks we've started loading; includes banks which are still loading, and may include
+    /// banks which have since been unloaded.
+    banks: RefCell<Vec<FmodOxideBank>>,
+    /// The listeners last set, so that one can be removed without the game setting them all again.
+    listeners: RefCell<Vec<AudioListener3d>>,
+    space: AudioSpaceConfig,
 }
 
 impl FmodOxideAudioBackend {
-    pub fn init(banks: &[String], base_path: &str) -> AudioResult<Box<Self>> {
+    /// Initialize FMOD and start loading the banks in the background, returning them so that they
+    /// can be polled until they're loaded. `output` is normally [fmod::OutputType::AutoDetect].
+    fn start_loading(
+        banks: &[String],
+        base_path: &str,
+        output: fmod::OutputType,
+        space: AudioSpaceConfig,
+    ) -> AudioResult<(Box<Self>, Vec<FmodOxideBank>)> {
+        space.validate()?;
+        let backend = Self::create(!banks.is_empty(), base_path, output, space)?;
+        let banks = banks
+            .iter()
+            .map(|bank_filename| {
+                backend.load_bank_file(bank_filename, fmod::studio::LoadBankFlags::NONBLOCKING)
+            })
+            .collect::<AudioResult<Vec<_>>>()?;
+        Ok((backend, banks))
+    }
+
+    fn create(
+        loading_banks: bool,
+        base_path: &str,
+        output: fmod::OutputType,
+        space: AudioSpaceConfig,
+    ) -> AudioResult<Box<Self>> {
         fmod::debug::initialize(
             fmod::debug::DebugFlags::LOG,
             fmod::debug::DebugMode::Callback(fmod_log_msg_callback),
@@ -60,13 +239,17 @@ impl FmodOxideAudioBackend {
         // The example Studio project is authored for 5.1 sound, so set up the system output mode to match
         builder
             .core_builder()
-            .software_format(0, fmod::SpeakerMode::FivePointOne, 0)?;
+            .software_format(0, fmod::SpeakerMode::FivePointOne, 0)?
+            .output(output)?;
 
-        // make sure the expected audio directory exists
-        //TODO audio: load banks via asset manager instead of filesystem paths?
+        // make sure the expected audio directory exists, unless banks are all going to be loaded
+        // from memory later
         let audio_dir = std::path::Path::new(base_path);
-        if !audio_dir.exists() {
-            bail!("audio directory not found: {}", audio_dir.display())
+        if loading_banks && !audio_dir.exists() {
+            return Err(AudioError::BankNotFound {
+                message: format!("audio directory not found: {}", audio_dir.display()),
+                code: None,
+            });
         }
 
         let studio_flags = if cfg!(debug_assertions) {
@@ -77,24 +260,56 @@ impl FmodOxideAudioBackend {
         };
         let system = builder.build(1024, studio_flags, fmod::InitFlags::NORMAL)?;
 
-        let mut loaded_banks = Vec::new();
-        for bank_filename in banks {
-            let loaded_bank = system.load_bank_file(
+        Ok(Box::new(FmodOxideAudioBackend {
+            system,
+            banks_path: audio_dir.to_owned(),
+            banks: RefCell::new(Vec::new()),
+            listeners: RefCell::new(Vec::new()),
+            space,
+        }))
+    }
+
+    fn load_bank_file(
+        &self,
+        filename: &str,
+        flags: fmod::studio::LoadBankFlags,
+    ) -> AudioResult<FmodOxideBank> {
+        let bank = self
+            .system
+            .load_bank_file(
                 &Utf8CString::new(
-                    audio_dir
-                        .join(bank_filename.as_str())
+                    self.banks_path
+                        .join(filename)
                         .to_str()
                         .expect("Bank path should be valid UTF-8 string"),
                 )?,
-                fmod::studio::LoadBankFlags::NORMAL,
-            )?;
-            loaded_banks.push((bank_filename.to_owned(), loaded_bank));
-        }
+                flags,
+            )
+            .with_context(|| format!("Loading bank: {filename}"))?;
+        let bank = FmodOxideBank {
+            filename: filename.to_owned(),
+            bank,
+            source: None,
+            space: self.space,
+        };
+        self.banks.borrow_mut().push(bank.clone());
+        Ok(bank)
+    }
 
-        Ok(Box::new(FmodOxideAudioBackend {
-            system,
-            banks: loaded_banks,
-        }))
+    /// Get the banks that are fully loaded, forgetting about any that have since been unloaded.
+    fn live_banks(&self) -> Vec<FmodOxideBank> {
+        let mut banks = self.banks.borrow_mut();
+        banks.retain(|b| b.bank.is_valid());
+        banks
+            .iter()
+            .filter(|b| {
+                matches!(
+                    b.bank.get_loading_state(),
+                    Ok(fmod::studio::LoadingState::Loaded)
+                )
+            })
+            .cloned()
+            .collect()
     }
 }
 
@@ -114,29 +329,169 @@ impl AudioBackend for FmodOxideAudioBackend {
 
     fn get_event(&self, event_name: &str) -> AudioResult<Box<dyn AudioEventDescription>> {
         let event_name_cstring = Utf8CString::new(event_name)?;
-        let event = self.system.get_event(&event_name_cstring)?;
+        let event = self
+            .system
+            .get_event(&event_name_cstring)
+            .map_err(|e| AudioError::from(e).for_event(event_name))?;
 
-        Ok(Box::new(event))
+        Ok(Box::new(FmodOxideEventDescription {
+            description: event,
+            space: self.space,
+        }))
     }
 
     fn get_event_list(&self) -> AudioResult<Vec<Box<dyn AudioEventDescription>>> {
         let mut all_events = Vec::new();
-        for (_bank_name, bank) in &self.banks {
-            let bank_events = bank
-                .get_event_list()
-                .with_context(|| format!("Getting event list for bank: {}", _bank_name))?;
-            all_events.extend(
-                bank_events
-                    .into_iter()
-                    .map(|e| Box::new(e) as Box<dyn AudioEventDescription>)
-                    .collect::<Vec<_>>(),
-            );
+        for bank in self.live_banks() {
+            all_events.extend(AudioBank::get_event_list(&bank)?);
         }
 
         Ok(all_events)
     }
 
+    fn get_bank(&self, filename: &str) -> AudioResult<Box<dyn AudioBank>> {
+        match self
+            .live_banks()
+            .into_iter()
+            .find(|b| b.filename == filename)
+        {
+            Some(bank) => Ok(Box::new(bank)),
+            None => Err(AudioError::BankNotFound {
+                message: format!("bank not loaded: {filename}"),
+                code: None,
+            }),
+        }
+    }
+
+    fn get_bank_list(&self) -> AudioResult<Vec<Box<dyn AudioBank>>> {
+        Ok(self
+            .live_banks()
+            .into_iter()
+            .map(|b| Box::new(b) as Box<dyn AudioBank>)
+            .collect())
+    }
+
+    fn load_bank(&self, filename: &str) -> AudioResult<Box<dyn AudioBankLoader>> {
+        let bank = self.load_bank_file(filename, fmod::studio::LoadBankFlags::NONBLOCKING)?;
+        Ok(Box::new(FmodOxideBankLoader { bank }))
+    }
+
+    fn load_bank_from_memory(&self, name: &str, data: &[u8]) -> AudioResult<Box<dyn AudioBank>> {
+        let bank = self
+            .system
+            .load_bank_memory(data, fmod::studio::LoadBankFlags::NORMAL)
+            .with_context(|| format!("Loading bank from memory: {name}"))?;
+        let bank = FmodOxideBank {
+            filename: name.to_owned(),
+            bank,
+            source: None,
+            space: self.space,
+        };
+        self.banks.borrow_mut().push(bank.clone());
+        Ok(Box::new(bank))
+    }
+
+    fn load_bank_from_source(
+        &self,
+        name: &str,
+        source: Arc<dyn AudioAssetSource>,
+    ) -> AudioResult<Box<dyn AudioBank>> {
+        let custom_source = Arc::new(CustomBankSource {
+            name: name.to_owned(),
+            source,
+        });
+        let info = fmod_sys::FMOD_STUDIO_BANK_INFO {
+            size: std::mem::size_of::<fmod_sys::FMOD_STUDIO_BANK_INFO>() as c_int,
+            userdata: Arc::as_ptr(&custom_source) as *mut c_void,
+            userdatalength: 0,
+            opencallback: Some(asset_source_open_callback),
+            closecallback: Some(asset_source_close_callback),
+            readcallback: Some(asset_source_read_callback),
+            seekcallback: Some(asset_source_seek_callback),
+        };
+
+        let mut bank = std::ptr::null_mut();
+        unsafe {
+            // Safety: fmod-oxide doesn't wrap this yet (its load_bank_custom is unimplemented), so
+            // we call FMOD directly. FMOD copies info, and the userdata it points to is kept alive
+            // by the bank we return.
+            fmod_sys::FMOD_Studio_System_LoadBankCustom(
+                self.system.into(),
+                &info,
+                fmod::studio::LoadBankFlags::NORMAL.bits(),
+                &mut bank,
+            )
+            .to_result()
+        }
+        .with_context(|| format!("Loading bank from source: {name}"))?;
+
+        let bank = FmodOxideBank {
+            filename: name.to_owned(),
+            bank: fmod::studio::Bank::from(bank),
+            source: Some(custom_source),
+            space: self.space,
+        };
+        self.banks.borrow_mut().push(bank.clone());
+        Ok(Box::new(bank))
+    }
+
+    fn unload_bank(&self, filename: &str) -> AudioResult<()> {
+        let bank = {
+            let mut banks = self.banks.borrow_mut();
+            // banks unloaded through their own handle are still in the list, and may have been
+            // loaded again under the same name since
+            banks.retain(|b| b.bank.is_valid());
+            match banks.iter().position(|b| b.filename == filename) {
+                Some(idx) => banks.remove(idx),
+                None => {
+                    return Err(AudioError::BankNotFound {
+                        message: format!("bank not loaded: {filename}"),
+                        code: None,
+                    });
+                }
+            }
+        };
+        Box::new(bank).unload()
+    }
+
+    fn get_bus(&self, path: &str) -> AudioResult<Box<dyn AudioBus>> {
+        let bus = self
+            .system
+            .get_bus(&Utf8CString::new(path)?)
+            .with_context(|| format!("Getting bus {path}"))?;
+        Ok(Box::new(bus))
+    }
+
+    fn get_vca(&self, path: &str) -> AudioResult<Box<dyn AudioVca>> {
+        let vca = self
+            .system
+            .get_vca(&Utf8CString::new(path)?)
+            .with_context(|| format!("Getting VCA {path}"))?;
+        Ok(Box::new(vca))
+    }
+
+    fn vca_list(&self) -> AudioResult<Vec<Box<dyn AudioVca>>> {
+        let mut all_vcas = Vec::new();
+        for bank in self.live_banks() {
+            let vcas = get_bank_vca_list(bank.bank)
+                .with_context(|| format!("Getting VCA list for bank: {}", bank.filename))?;
+            all_vcas.extend(vcas.into_iter().map(|v| Box::new(v) as Box<dyn AudioVca>));
+        }
+        Ok(all_vcas)
+    }
+
     fn set_listeners(&self, listeners: &[AudioListener]) -> AudioResult<()> {
+        let listeners: Vec<_> = listeners.iter().map(|l| l.to_3d(&self.space)).collect();
+        self.set_listeners_3d(&listeners)
+    }
+
+    fn set_listeners_3d(&self, listeners: &[AudioListener3d]) -> AudioResult<()> {
+        if listeners.is_empty() {
+            return Err(AudioError::InvalidParameter {
+                message: "At least one listener is needed".to_owned(),
+                code: None,
+            });
+        }
         // make sure we don't exceed the max number of listeners
         let listener_count = listeners.len().min(fmod::MAX_LISTENERS as usize);
         let listeners = &listeners[..listener_count];
@@ -147,17 +502,61 @@ impl AudioBackend for FmodOxideAudioBackend {
 
         // update listener positions
         for (i, listener) in listeners.iter().enumerate() {
+            let attenuation_position = listener
+                .attenuation_position
+                .map(|p| fmod_vector(self.space.fmod_position(p)));
             self.system
                 .set_listener_attributes(
                     i as c_int,
-                    build_3d_attrs(listener.position, listener.velocity),
-                    None,
+                    build_3d_attrs(&self.space, &listener.attributes),
+                    attenuation_position,
                 )
                 .with_context(|| format!("Setting listener {i} 3D attributes"))?;
             self.system
                 .set_listener_weight(i as c_int, listener.weight)
                 .with_context(|| format!("Setting listener {i} weight"))?;
         }
+        *self.listeners.borrow_mut() = listeners.to_vec();
+        Ok(())
+    }
+
+    fn set_listener_weight(&self, index: usize, weight: f32) -> AudioResult<()> {
+        let mut listeners = self.listeners.borrow_mut();
+        let Some(listener) = listeners.get_mut(index) else {
+            return Err(AudioError::InvalidParameter {
+                message: format!("No listener {index}"),
+                code: None,
+            });
+        };
+        self.system
+            .set_listener_weight(index as c_int, weight)
+            .with_context(|| format!("Setting listener {index} weight"))?;
+        listener.weight = weight;
+        Ok(())
+    }
+
+    fn remove_listener(&self, index: usize) -> AudioResult<()> {
+        let mut listeners = self.listeners.borrow().clone();
+        if index >= listeners.len() {
+            return Err(AudioError::InvalidParameter {
+                message: format!("No listener {index}"),
+                code: None,
+            });
+        }
+        listeners.remove(index);
+        self.set_listeners_3d(&listeners)
+    }
+
+    fn set_3d_settings(
+        &self,
+        doppler_scale: f32,
+        distance_factor: f32,
+        rolloff_scale: f32,
+    ) -> AudioResult<()> {
+        self.system
+            .get_core_system()?
+            .set_3d_settings(doppler_scale, distance_factor, rolloff_scale)
+            .context("Setting 3D settings")?;
         Ok(())
     }
 
@@ -170,6 +569,40 @@ impl AudioBackend for FmodOxideAudioBackend {
         )?;
         Ok(())
     }
+
+    fn global_parameters(&self) -> AudioResult<Vec<AudioParameterDescription>> {
+        let descriptions = self
+            .system
+            .get_parameter_description_list()
+            .context("Getting global parameters")?;
+        descriptions
+            .into_iter()
+            .map(|description| {
+                let id = description.id;
+                convert_parameter_description(description, |label_index| {
+                    self.system.get_parameter_label_by_id(id, label_index)
+                })
+            })
+            .collect()
+    }
+
+    fn get_parameter_handle(&self, name: &str) -> AudioResult<ParameterHandle> {
+        let description = self
+            .system
+            .get_parameter_description_by_name(&Utf8CString::new(name)?)
+            .with_context(|| format!("Looking up global parameter {name}"))?;
+        Ok(ParameterHandle {
+            name: name.to_owned(),
+            id: convert_parameter_id(description.id),
+        })
+    }
+
+    fn set_parameter(&self, handle: &ParameterHandle, value: f32) -> AudioResult<()> {
+        self.system
+            .set_parameter_by_id(build_parameter_id(handle.id()), value, false)
+            .with_context(|| format!("Setting global parameter {}", handle.name()))?;
+        Ok(())
+    }
 }
 
 unsafe extern "C" fn fmod_log_msg_callback(
@@ -230,70 +663,329 @@ unsafe extern "C" fn fmod_log_msg_callback(
     fmod_sys::FMOD_RESULT::FMOD_OK
 }
 
-fn build_3d_attrs(position: Vec2, velocity: Vec2) -> fmod::Attributes3D {
-    // this is specific to my game: positive y is down, and we have 10 pixels to 1 meter.
-    // (Theoretically, we should be able to just set the core system's 3d settings, but seems like
-    // sounds are still attenuated too much that way, so we scale things here instead.)
-    const UP_DIR: f32 = -1.0;
+fn build_3d_attrs(space: &AudioSpaceConfig, attributes: &Audio3dAttributes) -> fmod::Attributes3D {
+    // positions are scaled to metres here; FMOD's own distance factor is left to
+    // AudioBackend::set_3d_settings
+    let attributes = space.fmod_attributes(attributes);
     fmod::Attributes3D {
-        position: fmod::Vector {
-            x: -position.x / 10.0,
-            y: position.y / 10.0,
-            z: 0.0,
-        },
-        velocity: fmod::Vector {
-            x: -velocity.x / 10.0,
-            y: velocity.y / 10.0,
-            z: 0.0,
-        },
-        forward: fmod::Vector {
-            x: 0.0,
-            y: 0.0,
-            z: 1.0,
-        },
-        up: fmod::Vector {
-            x: 0.0,
-            y: UP_DIR,
-            z: 0.0,
-        },
+        position: fmod_vector(attributes.position),
+        velocity: fmod_vector(attributes.velocity),
+        forward: fmod_vector(attributes.forward),
+        up: fmod_vector(attributes.up),
+    }
+}
+
+fn fmod_vector(v: Vec3) -> fmod::Vector {
+    fmod::Vector {
+        x: v.x,
+        y: v.y,
+        z: v.z,
+    }
+}
+
+/// Banks are loaded with [fmod::studio::LoadBankFlags::NONBLOCKING], so FMOD loads them on its own
+/// thread and we just need to poll the loading state. FMOD doesn't tell us how much of a bank has
+/// been read, so progress jumps straight from 0 to 1.
+struct FmodOxideBankLoader {
+    bank: FmodOxideBank,
+}
+
+impl AudioBankLoader for FmodOxideBankLoader {
+    fn get_progress(&self) -> f32 {
+        // failed loads have finished too
+        match self.bank.bank.get_loading_state() {
+            Ok(fmod::studio::LoadingState::Loading) => 0.0,
+            _ => 1.0,
+        }
+    }
+
+    fn get_loaded(&self) -> Option<AudioResult<Box<dyn AudioBank>>> {
+        match self.bank.bank.get_loading_state() {
+            Ok(fmod::studio::LoadingState::Loaded) => Some(Ok(Box::new(self.bank.clone()))),
+            Ok(fmod::studio::LoadingState::Loading) => None,
+            Ok(fmod::studio::LoadingState::Error(e)) | Err(e) => {
+                // failed asynchronous loads still need to be unloaded to release the bank handle
+                let _ = self.bank.bank.unload();
+                Some(Err(
+                    AudioError::from(e).context(format!("Loading bank: {}", self.bank.filename))
+                ))
+            }
+            Ok(state) => Some(Err(AudioError::other(format!(
+                "Bank {} unexpectedly in state {state:?} while loading",
+                self.bank.filename
+            )))),
+        }
     }
 }
 
-impl AudioEventDescription for fmod::studio::EventDescription {
+#[derive(Clone)]
+struct FmodOxideBank {
+    filename: String,
+    bank: fmod::studio::Bank,
+    /// For banks loaded from an [AudioAssetSource], FMOD holds a pointer to this, so it needs to
+    /// live as long as the bank does.
+    source: Option<Arc<CustomBankSource>>,
+    /// Passed on to the bank's events.
+    space: AudioSpaceConfig,
+}
+
+/// Userdata passed to FMOD's file callbacks for banks loaded via
+/// [AudioBackend::load_bank_from_source].
+struct CustomBankSource {
+    name: String,
+    source: Arc<dyn AudioAssetSource>,
+}
+
+/// Run asset source code (which is supplied by the game) from inside an FMOD file callback, making
+/// sure that panics don't unwind into FMOD.
+fn catch_asset_source_panic<T>(f: impl FnOnce() -> AudioResult<T>) -> AudioResult<T> {
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(f))
+        .unwrap_or_else(|_| Err(AudioError::other("audio asset source panicked")))
+}
+
+unsafe extern "C" fn asset_source_open_callback(
+    _name: *const c_char,
+    filesize: *mut c_uint,
+    handle: *mut *mut c_void,
+    userdata: *mut c_void,
+) -> fmod_sys::FMOD_RESULT {
+    // Safety: userdata is the CustomBankSource passed to loadBankCustom, which outlives the bank
+    let custom_source = unsafe { &*(userdata as *const CustomBankSource) };
+
+    let stream = match catch_asset_source_panic(|| custom_source.source.open(&custom_source.name)) {
+        Ok(stream) => stream,
+        Err(e) => {
+            error!("Opening audio asset {}: {e:?}", custom_source.name);
+            return fmod_sys::FMOD_RESULT::FMOD_ERR_FILE_NOTFOUND;
+        }
+    };
+    let Ok(size) = c_uint::try_from(stream.size()) else {
+        error!("Audio asset {} is too large for FMOD", custom_source.name);
+        return fmod_sys::FMOD_RESULT::FMOD_ERR_FILE_BAD;
+    };
+
+    unsafe {
+        *filesize = size;
+        // double boxed so that the handle is a thin pointer; freed in the close callback
+        *handle = Box::into_raw(Box::new(stream)).cast();
+    }
+    fmod_sys::FMOD_RESULT::FMOD_OK
+}
+
+unsafe extern "C" fn asset_source_close_callback(
+    handle: *mut c_void,
+    _userdata: *mut c_void,
+) -> fmod_sys::FMOD_RESULT {
+    // Safety: handle was created by the open callback and FMOD closes each handle exactly once
+    drop(unsafe { Box::from_raw(handle.cast::<Box<dyn AudioAssetStream>>()) });
+    fmod_sys::FMOD_RESULT::FMOD_OK
+}
+
+unsafe extern "C" fn asset_source_read_callback(
+    handle: *mut c_void,
+    buffer: *mut c_void,
+    sizebytes: c_uint,
+    bytesread: *mut c_uint,
+    _userdata: *mut c_void,
+) -> fmod_sys::FMOD_RESULT {
+    // Safety: handle was created by the open callback, and FMOD doesn't use a handle from multiple
+    // threads at once
+    let stream = unsafe { &mut *handle.cast::<Box<dyn AudioAssetStream>>() };
+    let buffer = unsafe { std::slice::from_raw_parts_mut(buffer.cast::<u8>(), sizebytes as usize) };
+
+    // FMOD treats a short read as reaching the end of the file, so keep going until the buffer is
+    // full or the stream runs out
+    let result = catch_asset_source_panic(|| {
+        let mut total = 0;
+        while total < buffer.len() {
+            let read = stream.read(&mut buffer[total..])?;
+            if read == 0 {
+                break;
+            }
+            total += read;
+        }
+        Ok(total)
+    });
+
+    match result {
+        Ok(total) => {
+            unsafe { *bytesread = total as c_uint };
+            if total < buffer.len() {
+                fmod_sys::FMOD_RESULT::FMOD_ERR_FILE_EOF
+            } else {
+                fmod_sys::FMOD_RESULT::FMOD_OK
+            }
+        }
+        Err(e) => {
+            error!("Reading audio asset: {e:?}");
+            fmod_sys::FMOD_RESULT::FMOD_ERR_FILE_BAD
+        }
+    }
+}
+
+unsafe extern "C" fn asset_source_seek_callback(
+    handle: *mut c_void,
+    pos: c_uint,
+    _userdata: *mut c_void,
+) -> fmod_sys::FMOD_RESULT {
+    // Safety: as for the read callback
+    let stream = unsafe { &mut *handle.cast::<Box<dyn AudioAssetStream>>() };
+
+    match catch_asset_source_panic(|| stream.seek(pos as u64)) {
+        Ok(()) => fmod_sys::FMOD_RESULT::FMOD_OK,
+        Err(e) => {
+            error!("Seeking audio asset: {e:?}");
+            fmod_sys::FMOD_RESULT::FMOD_ERR_FILE_COULDNOTSEEK
+        }
+    }
+}
+
+fn convert_loading_state(state: fmod::studio::LoadingState) -> AudioLoadingState {
+    match state {
+        fmod::studio::LoadingState::Unloading => AudioLoadingState::Unloading,
+        fmod::studio::LoadingState::Unloaded => AudioLoadingState::Unloaded,
+        fmod::studio::LoadingState::Loading => AudioLoadingState::Loading,
+        fmod::studio::LoadingState::Loaded => AudioLoadingState::Loaded,
+        fmod::studio::LoadingState::Error(e) => {
+            warn!("FMOD loading error: {e}");
+            AudioLoadingState::Error
+        }
+    }
+}
+
+impl AudioBank for FmodOxideBank {
+    fn get_filename(&self) -> String {
+        self.filename.clone()
+    }
+
+    fn get_path(&self) -> AudioResult<String> {
+        let path = self.bank.get_path()?;
+        Ok(path.as_str().to_owned())
+    }
+
+    fn get_id(&self) -> AudioResult<String> {
+        Ok(self.bank.get_id()?.to_string())
+    }
+
+    fn get_loading_state(&self) -> AudioResult<AudioLoadingState> {
+        Ok(convert_loading_state(self.bank.get_loading_state()?))
+    }
+
+    fn get_sample_loading_state(&self) -> AudioResult<AudioLoadingState> {
+        Ok(convert_loading_state(self.bank.get_sample_loading_state()?))
+    }
+
+    fn load_sample_data(&self) -> AudioResult<()> {
+        self.bank.load_sample_data()?;
+        Ok(())
+    }
+
+    fn unload_sample_data(&self) -> AudioResult<()> {
+        self.bank.unload_sample_data()?;
+        Ok(())
+    }
+
+    fn get_event_list(&self) -> AudioResult<Vec<Box<dyn AudioEventDescription>>> {
+        let bank_events = self
+            .bank
+            .get_event_list()
+            .with_context(|| format!("Getting event list for bank: {}", self.filename))?;
+        Ok(bank_events
+            .into_iter()
+            .map(|description| {
+                Box::new(FmodOxideEventDescription {
+                    description,
+                    space: self.space,
+                }) as Box<dyn AudioEventDescription>
+            })
+            .collect())
+    }
+
+    fn unload(self: Box<Self>) -> AudioResult<()> {
+        self.bank
+            .unload()
+            .with_context(|| format!("Unloading bank: {}", self.filename))?;
+        Ok(())
+    }
+}
+
+/// FMOD's event handles don't know which backend they came from, so descriptions and instances
+/// carry the backend's coordinates with them.
+struct FmodOxideEventDescription {
+    description: fmod::studio::EventDescription,
+    space: AudioSpaceConfig,
+}
+
+impl AudioEventDescription for FmodOxideEventDescription {
     fn create_instance(&self) -> AudioResult<Box<dyn AudioEventInstance>> {
-        let instance = fmod::studio::EventDescription::create_instance(&self)?;
-        Ok(Box::new(instance))
+        Ok(Box::new(FmodOxideEventInstance {
+            instance: self.description.create_instance()?,
+            space: self.space,
+        }))
     }
 
     fn get_path(&self) -> AudioResult<String> {
-        let path = fmod::studio::EventDescription::get_path(&self)?;
+        let path = self.description.get_path()?;
         Ok(path.as_str().to_owned())
     }
+
+    fn parameters(&self) -> AudioResult<Vec<AudioParameterDescription>> {
+        let count = self
+            .description
+            .parameter_description_count()
+            .context("Getting parameter count")?;
+        (0..count)
+            .map(|index| {
+                let description = self
+                    .description
+                    .get_parameter_description_by_index(index)
+                    .with_context(|| format!("Getting parameter description {index}"))?;
+                convert_parameter_description(description, |label_index| {
+                    self.description
+                        .get_parameter_label_by_index(index, label_index)
+                })
+            })
+            .collect()
+    }
+
+    fn is_snapshot(&self) -> AudioResult<bool> {
+        Ok(self.description.is_snapshot()?)
+    }
 }
 
-impl AudioEventInstance for fmod::studio::EventInstance {
+struct FmodOxideEventInstance {
+    instance: fmod::studio::EventInstance,
+    space: AudioSpaceConfig,
+}
+
+impl AudioEventInstance for FmodOxideEventInstance {
     fn release(self: Box<Self>) -> AudioResult<()> {
-        fmod::studio::EventInstance::release(*self)?;
+        self.instance.release()?;
         Ok(())
     }
 
     fn start(&self) -> AudioResult<()> {
-        fmod::studio::EventInstance::start(self)?;
+        self.instance.start()?;
         Ok(())
     }
 
-    fn stop(&self) -> AudioResult<()> {
-        fmod::studio::EventInstance::stop(self, fmod::studio::StopMode::AllowFadeout)?;
+    fn stop(&self, mode: AudioStopMode) -> AudioResult<()> {
+        self.instance.stop(build_stop_mode(mode))?;
         Ok(())
     }
 
     fn set_3d_attributes(&self, position: Vec2, velocity: Vec2) -> AudioResult<()> {
-        fmod::studio::EventInstance::set_3d_attributes(self, build_3d_attrs(position, velocity))?;
+        self.set_attributes_3d(&self.space.attributes_from_2d(position, velocity))
+    }
+
+    fn set_attributes_3d(&self, attributes: &Audio3dAttributes) -> AudioResult<()> {
+        self.instance
+            .set_3d_attributes(build_3d_attrs(&self.space, attributes))?;
         Ok(())
     }
 
     fn get_playback_state(&self) -> AudioResult<AudioPlaybackState> {
-        let state = fmod::studio::EventInstance::get_playback_state(self)?;
+        let state = self.instance.get_playback_state()?;
         Ok(match state {
             fmod::studio::PlaybackState::Playing => AudioPlaybackState::Playing,
             fmod::studio::PlaybackState::Sustaining => AudioPlaybackState::Sustaining,
@@ -302,4 +994,391 @@ impl AudioEventInstance for fmod::studio::EventInstance {
             fmod::studio::PlaybackState::Stopping => AudioPlaybackState::Stopping,
         })
     }
+
+    fn set_parameter_by_name(&self, name: &str, value: f32) -> AudioResult<()> {
+        self.instance
+            .set_parameter_by_name(&Utf8CString::new(name)?, value, false)
+            .with_context(|| format!("Setting parameter {name}"))?;
+        Ok(())
+    }
+
+    fn set_parameter_by_id(&self, id: AudioParameterId, value: f32) -> AudioResult<()> {
+        self.instance
+            .set_parameter_by_id(build_parameter_id(id), value, false)
+            .with_context(|| format!("Setting parameter {id:?}"))?;
+        Ok(())
+    }
+
+    fn set_parameter_by_name_with_label(&self, name: &str, label: &str) -> AudioResult<()> {
+        self.instance
+            .set_parameter_by_name_with_label(
+                &Utf8CString::new(name)?,
+                &Utf8CString::new(label)?,
+                false,
+            )
+            .with_context(|| format!("Setting parameter {name} to label {label}"))?;
+        Ok(())
+    }
+
+    fn get_parameter_by_name(&self, name: &str) -> AudioResult<AudioParameterValue> {
+        let (value, final_value) = self
+            .instance
+            .get_parameter_by_name(&Utf8CString::new(name)?)
+            .with_context(|| format!("Getting parameter {name}"))?;
+        Ok(AudioParameterValue { value, final_value })
+    }
+
+    fn enable_callbacks(&self) -> AudioResult<()> {
+        use fmod::studio::EventCallbackMask;
+
+        self.instance
+            .set_userdata(Arc::new(EventCallbackQueue::default()))
+            .context("Setting event callback queue")?;
+        self.instance
+            .set_callback::<QueueingEventCallback>(
+                EventCallbackMask::STARTING
+                    | EventCallbackMask::STARTED
+                    | EventCallbackMask::RESTARTED
+                    | EventCallbackMask::STOPPED
+                    | EventCallbackMask::START_FAILED
+                    | EventCallbackMask::TIMELINE_MARKER
+                    | EventCallbackMask::TIMELINE_BEAT
+                    | EventCallbackMask::SOUND_PLAYED
+                    | EventCallbackMask::SOUND_STOPPED
+                    | EventCallbackMask::REAL_TO_VIRTUAL
+                    | EventCallbackMask::VIRTUAL_TO_REAL,
+            )
+            .context("Setting event callback")?;
+        Ok(())
+    }
+
+    fn poll_callbacks(&self) -> AudioResult<Vec<AudioEventCallback>> {
+        let Some(userdata) = self.instance.get_userdata()? else {
+            return Err(AudioError::other(
+                "Callbacks haven't been enabled for this event instance",
+            ));
+        };
+        let Some(queue) = userdata.downcast_ref::<EventCallbackQueue>() else {
+            return Err(AudioError::other(
+                "Event instance userdata isn't a callback queue",
+            ));
+        };
+        Ok(std::mem::take(&mut *queue.lock().unwrap()))
+    }
+}
+
+/// Set as an event instance's userdata by [AudioEventInstance::enable_callbacks]. Callbacks come in
+/// on FMOD's studio update thread, hence the mutex.
+type EventCallbackQueue = Mutex<Vec<AudioEventCallback>>;
+
+struct QueueingEventCallback;
+
+impl QueueingEventCallback {
+    fn push(event: fmod::studio::EventInstance, callback: AudioEventCallback) -> fmod::Result<()> {
+        if let Some(userdata) = event.get_userdata()?
+            && let Some(queue) = userdata.downcast_ref::<EventCallbackQueue>()
+        {
+            queue.lock().unwrap().push(callback);
+        }
+        Ok(())
+    }
+}
+
+impl fmod::studio::EventInstanceCallback for QueueingEventCallback {
+    fn starting(event: fmod::studio::EventInstance) -> fmod::Result<()> {
+        Self::push(event, AudioEventCallback::Starting)
+    }
+
+    fn started(event: fmod::studio::EventInstance) -> fmod::Result<()> {
+        Self::push(event, AudioEventCallback::Started)
+    }
+
+    fn restarted(event: fmod::studio::EventInstance) -> fmod::Result<()> {
+        Self::push(event, AudioEventCallback::Restarted)
+    }
+
+    fn stopped(event: fmod::studio::EventInstance) -> fmod::Result<()> {
+        Self::push(event, AudioEventCallback::Stopped)
+    }
+
+    fn start_failed(event: fmod::studio::EventInstance) -> fmod::Result<()> {
+        Self::push(event, AudioEventCallback::StartFailed)
+    }
+
+    fn timeline_marker(
+        event: fmod::studio::EventInstance,
+        marker: fmod::studio::TimelineMarkerProperties,
+    ) -> fmod::Result<()> {
+        Self::push(
+            event,
+            AudioEventCallback::TimelineMarker {
+                name: marker.name.as_str().to_owned(),
+                position: marker.position,
+            },
+        )
+    }
+
+    fn timeline_beat(
+        event: fmod::studio::EventInstance,
+        beat: fmod::studio::TimelineBeatProperties,
+    ) -> fmod::Result<()> {
+        Self::push(
+            event,
+            AudioEventCallback::TimelineBeat {
+                bar: beat.bar,
+                beat: beat.beat,
+                position: beat.position,
+                tempo: beat.tempo,
+                time_signature_upper: beat.time_signature_upper,
+                time_signature_lower: beat.time_signature_lower,
+            },
+        )
+    }
+
+    fn sound_played(event: fmod::studio::EventInstance, _sound: fmod::Sound) -> fmod::Result<()> {
+        Self::push(event, AudioEventCallback::SoundPlayed)
+    }
+
+    fn sound_stopped(event: fmod::studio::EventInstance, _sound: fmod::Sound) -> fmod::Result<()> {
+        Self::push(event, AudioEventCallback::SoundStopped)
+    }
+
+    fn real_to_virtual(event: fmod::studio::EventInstance) -> fmod::Result<()> {
+        Self::push(event, AudioEventCallback::RealToVirtual)
+    }
+
+    fn virtual_to_real(event: fmod::studio::EventInstance) -> fmod::Result<()> {
+        Self::push(event, AudioEventCallback::VirtualToReal)
+    }
+}
+
+/// `get_label` gets the label at an index, for labeled parameters; it's passed in since FMOD looks
+/// labels up differently for event and global parameters.
+fn convert_parameter_description(
+    description: fmod::studio::ParameterDescription,
+    get_label: impl Fn(c_int) -> fmod::Result<Utf8CString>,
+) -> AudioResult<AudioParameterDescription> {
+    use fmod::studio::{ParameterFlags, ParameterKind};
+
+    let name = description.name.as_str().to_owned();
+    let flags = AudioParameterFlags {
+        read_only: description.flags.contains(ParameterFlags::READONLY),
+        automatic: description.flags.contains(ParameterFlags::AUTOMATIC),
+        global: description.flags.contains(ParameterFlags::GLOBAL),
+        discrete: description.flags.contains(ParameterFlags::DISCRETE),
+        labeled: description.flags.contains(ParameterFlags::LABELED),
+    };
+
+    let mut labels = Vec::new();
+    if flags.labeled {
+        // labeled parameters have one label per integer value in their range
+        let label_count = (description.maximum - description.minimum).round() as c_int + 1;
+        for label_index in 0..label_count {
+            let label = get_label(label_index)
+                .with_context(|| format!("Getting label {label_index} of parameter {name}"))?;
+            labels.push(label.as_str().to_owned());
+        }
+    }
+
+    Ok(AudioParameterDescription {
+        id: convert_parameter_id(description.id),
+        minimum: description.minimum,
+        maximum: description.maximum,
+        default_value: description.default_value,
+        kind: match description.kind {
+            ParameterKind::GameControlled => AudioParameterKind::GameControlled,
+            ParameterKind::AutomaticDistance => AudioParameterKind::AutomaticDistance,
+            ParameterKind::AutomaticEventConeAngle => AudioParameterKind::AutomaticEventConeAngle,
+            ParameterKind::AutomaticEventOrientation => {
+                AudioParameterKind::AutomaticEventOrientation
+            }
+            ParameterKind::AutomaticDirection => AudioParameterKind::AutomaticDirection,
+            ParameterKind::AutomaticElevation => AudioParameterKind::AutomaticElevation,
+            ParameterKind::AutomaticListenerOrientation => {
+                AudioParameterKind::AutomaticListenerOrientation
+            }
+            ParameterKind::AutomaticSpeed => AudioParameterKind::AutomaticSpeed,
+            ParameterKind::AutomaticSpeedAbsolute => AudioParameterKind::AutomaticSpeedAbsolute,
+            ParameterKind::AutomaticDistanceNormalized => {
+                AudioParameterKind::AutomaticDistanceNormalized
+            }
+        },
+        flags,
+        labels,
+        name,
+    })
+}
+
+fn convert_parameter_id(id: fmod::studio::ParameterID) -> AudioParameterId {
+    AudioParameterId {
+        data1: id.data_1,
+        data2: id.data_2,
+    }
+}
+
+fn build_parameter_id(id: AudioParameterId) -> fmod::studio::ParameterID {
+    fmod::studio::ParameterID {
+        data_1: id.data1,
+        data_2: id.data2,
+    }
+}
+
+impl AudioBus for fmod::studio::Bus {
+    fn get_path(&self) -> AudioResult<String> {
+        let path = fmod::studio::Bus::get_path(self)?;
+        Ok(path.as_str().to_owned())
+    }
+
+    fn set_volume(&self, volume: f32) -> AudioResult<()> {
+        fmod::studio::Bus::set_volume(self, volume)?;
+        Ok(())
+    }
+
+    fn get_volume(&self) -> AudioResult<AudioVolume> {
+        let (volume, final_volume) = fmod::studio::Bus::get_volume(self)?;
+        Ok(AudioVolume {
+            volume,
+            final_volume,
+        })
+    }
+
+    fn set_mute(&self, mute: bool) -> AudioResult<()> {
+        fmod::studio::Bus::set_mute(self, mute)?;
+        Ok(())
+    }
+
+    fn get_mute(&self) -> AudioResult<bool> {
+        Ok(fmod::studio::Bus::get_mute(self)?)
+    }
+
+    fn set_paused(&self, paused: bool) -> AudioResult<()> {
+        fmod::studio::Bus::set_paused(self, paused)?;
+        Ok(())
+    }
+
+    fn get_paused(&self) -> AudioResult<bool> {
+        Ok(fmod::studio::Bus::get_paused(self)?)
+    }
+
+    fn stop_all_events(&self, mode: AudioStopMode) -> AudioResult<()> {
+        fmod::studio::Bus::stop_all_events(self, build_stop_mode(mode))?;
+        Ok(())
+    }
+
+    fn enable_metering(&self) -> AudioResult<()> {
+        self.lock_channel_group()
+            .context("Locking bus channel group")?;
+        // the channel group gets created asynchronously, so if it isn't there yet, metering on its
+        // DSP is enabled once it turns up in get_metering
+        if let Some(dsp) = get_bus_head_dsp(self)? {
+            dsp.set_metering_enabled(false, true)?;
+        }
+        Ok(())
+    }
+
+    fn get_metering(&self) -> AudioResult<Option<AudioMeteringInfo>> {
+        let Some(dsp) = get_bus_head_dsp(self)? else {
+            return Ok(None);
+        };
+        if !dsp.get_metering_enabled()?.1 {
+            // nothing has been measured yet, so the levels would all read zero
+            dsp.set_metering_enabled(false, true)?;
+            return Ok(None);
+        }
+
+        let (_input, output) = dsp.get_metering_info()?;
+        let channel_count = output.channel_count.max(0) as usize;
+        Ok(Some(AudioMeteringInfo {
+            peak_level: output.peak_level[..channel_count].to_vec(),
+            rms_level: output.rms_level[..channel_count].to_vec(),
+        }))
+    }
+}
+
+/// The DSP a bus's output levels are metered on, or `None` if its channel group doesn't exist yet.
+fn get_bus_head_dsp(bus: &fmod::studio::Bus) -> AudioResult<Option<fmod::Dsp>> {
+    let channel_group = match bus.get_channel_group() {
+        Ok(channel_group) => channel_group,
+        Err(e) if e == fmod_sys::FMOD_RESULT::FMOD_ERR_STUDIO_NOT_LOADED => return Ok(None),
+        Err(e) => return Err(e).context("Getting bus channel group"),
+    };
+    Ok(Some(
+        channel_group.get_dsp(fmod_sys::FMOD_CHANNELCONTROL_DSP_HEAD)?,
+    ))
+}
+
+impl AudioVca for fmod::studio::Vca {
+    fn get_path(&self) -> AudioResult<String> {
+        let path = fmod::studio::Vca::get_path(self)?;
+        Ok(path.as_str().to_owned())
+    }
+
+    fn set_volume(&self, volume: f32) -> AudioResult<()> {
+        fmod::studio::Vca::set_volume(self, volume)?;
+        Ok(())
+    }
+
+    fn get_volume(&self) -> AudioResult<AudioVolume> {
+        let (volume, final_volume) = fmod::studio::Vca::get_volume(self)?;
+        Ok(AudioVolume {
+            volume,
+            final_volume,
+        })
+    }
+}
+
+/// fmod-oxide's `Bank::get_vca_list` sizes the list by the bank's event count rather than its VCA
+/// count, so we call FMOD directly instead.
+fn get_bank_vca_list(bank: fmod::studio::Bank) -> AudioResult<Vec<fmod::studio::Vca>> {
+    let capacity = bank.vca_count()?;
+    let mut list = vec![std::ptr::null_mut(); capacity as usize];
+    let mut count = 0;
+    unsafe {
+        fmod_sys::FMOD_Studio_Bank_GetVCAList(bank.into(), list.as_mut_ptr(), capacity, &mut count)
+            .to_result()?;
+    }
+    list.truncate(count as usize);
+    Ok(list.into_iter().map(fmod::studio::Vca::from).collect())
+}
+
+fn build_stop_mode(mode: AudioStopMode) -> fmod::studio::StopMode {
+    match mode {
+        AudioStopMode::AllowFadeout => fmod::studio::StopMode::AllowFadeout,
+        AudioStopMode::Immediate => fmod::studio::StopMode::Immediate,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::conformance::{self, ConformanceFixture};
+    use super::*;
+
+    /// Runs against the banks from FMOD's Studio examples, so needs `FMOD_STUDIO_EXAMPLES_DIR` set to
+    /// e.g. `fmod/linux/api/studio/examples/media/`; run it with `cargo test -- --ignored`.
+    #[test]
+    #[ignore = "needs FMOD_STUDIO_EXAMPLES_DIR"]
+    fn desktop_backend_conforms() {
+        let examples_dir = std::env::var("FMOD_STUDIO_EXAMPLES_DIR")
+            .expect("FMOD_STUDIO_EXAMPLES_DIR should be set to FMOD's Studio examples media dir");
+        let vehicles_bank = std::path::Path::new(&examples_dir).join("Vehicles.bank");
+        let fixture = ConformanceFixture::fmod_examples(std::fs::read(vehicles_bank).unwrap());
+
+        let loader = start_loading_audio_backend(
+            &examples_dir,
+            &fixture.loaded_banks,
+            AudioSpaceConfig::default(),
+        );
+        let start = std::time::Instant::now();
+        let backend = loop {
+            if let Some(backend) = loader.get_loaded() {
+                break backend.unwrap();
+            }
+            assert!(
+                start.elapsed() < std::time::Duration::from_secs(10),
+                "banks took too long to load"
+            );
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        };
+        conformance::check_backend(backend, &fixture).unwrap();
+    }
 }
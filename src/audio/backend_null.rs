@@ -0,0 +1,1944 @@
+//! A headless audio backend that never touches FMOD, so that game logic which drives audio can be
+//! exercised in tests and on CI machines without libfmod or the HTML5 SDK.
+//!
+//! Nothing is played: the backend just records what it was asked to do. Playback states advance
+//! the way FMOD's do from the game's point of view - `start` puts an instance into
+//! [AudioPlaybackState::Starting] and the next [AudioBackend::update] moves it to
+//! [AudioPlaybackState::Playing]; likewise `stop` with [AudioStopMode::AllowFadeout] goes through
+//! [AudioPlaybackState::Stopping] to [AudioPlaybackState::Stopped], while
+//! [AudioStopMode::Immediate] stops straight away. Tests can then look at what happened via
+//! [NullAudioInspector].
+//!
+//! Like FMOD, it fails when asked for events, banks or parameters it hasn't been told about; see
+//! the conformance tests at the bottom for everything it has to do the same way as FMOD.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use super::error::*;
+use super::*;
+
+/// Everything the null backend knows about; shared between the backend, its event descriptions and
+/// instances, and any inspectors.
+#[derive(Debug, Default)]
+struct NullAudioState {
+    banks: Vec<NullBankRecord>,
+    instances: Vec<NullInstanceRecord>,
+    buses: Vec<NullBusRecord>,
+    /// Volumes of VCAs which have been set, by path.
+    vca_volumes: HashMap<String, f32>,
+    /// Used to turn 2D listeners and positions into 3D ones.
+    space: AudioSpaceConfig,
+    listeners: Vec<AudioListener3d>,
+    /// Doppler scale, distance factor and rolloff scale, if they've been set.
+    settings_3d: Option<[f32; 3]>,
+    parameters: HashMap<String, f32>,
+    /// Parameter descriptions by event path, as given to [NullAudioBackend::add_parameter].
+    event_parameters: HashMap<String, Vec<AudioParameterDescription>>,
+    /// As given to [NullAudioBackend::add_global_parameter].
+    global_parameters: Vec<AudioParameterDescription>,
+    update_count: u64,
+    shut_down: bool,
+}
+
+impl NullAudioState {
+    fn instance_mut(&mut self, id: usize) -> &mut NullInstanceRecord {
+        &mut self.instances[id]
+    }
+
+    fn bank(&self, filename: &str) -> AudioResult<&NullBankRecord> {
+        match self
+            .banks
+            .iter()
+            .find(|b| b.is_loaded() && b.filename == filename)
+        {
+            Some(bank) => Ok(bank),
+            None => Err(unloaded_bank_error(filename)),
+        }
+    }
+
+    fn bank_mut(&mut self, filename: &str) -> AudioResult<&mut NullBankRecord> {
+        match self
+            .banks
+            .iter_mut()
+            .find(|b| b.is_loaded() && b.filename == filename)
+        {
+            Some(bank) => Ok(bank),
+            None => Err(unloaded_bank_error(filename)),
+        }
+    }
+
+    fn bus_mut(&mut self, path: &str) -> AudioResult<&mut NullBusRecord> {
+        match self.buses.iter_mut().find(|b| b.path == path) {
+            Some(bus) => Ok(bus),
+            None => Err(AudioError::from_fmod_code(
+                FMOD_ERR_EVENT_NOTFOUND,
+                format!("null audio backend: bus not found: {path}"),
+            )),
+        }
+    }
+
+    fn has_vca(&self, path: &str) -> bool {
+        self.banks
+            .iter()
+            .any(|b| b.is_loaded() && b.vca_paths.iter().any(|p| p == path))
+    }
+
+    fn has_event(&self, event_path: &str) -> bool {
+        self.banks
+            .iter()
+            .any(|b| b.is_loaded() && b.event_paths.iter().any(|p| p == event_path))
+    }
+
+    fn parameter_by_name(
+        &self,
+        instance_id: usize,
+        name: &str,
+    ) -> AudioResult<AudioParameterDescription> {
+        match self.find_parameter(instance_id, |p| p.name == name) {
+            Some(description) => Ok(description),
+            None => Err(AudioError::from_fmod_code(
+                FMOD_ERR_EVENT_NOTFOUND,
+                format!("null audio backend: parameter not found: {name}"),
+            )),
+        }
+    }
+
+    fn parameter_by_id(
+        &self,
+        instance_id: usize,
+        id: AudioParameterId,
+    ) -> AudioResult<AudioParameterDescription> {
+        match self.find_parameter(instance_id, |p| p.id == id) {
+            Some(description) => Ok(description),
+            None => Err(AudioError::from_fmod_code(
+                FMOD_ERR_EVENT_NOTFOUND,
+                format!("null audio backend: parameter not found: {id:?}"),
+            )),
+        }
+    }
+
+    fn find_parameter(
+        &self,
+        instance_id: usize,
+        matches: impl Fn(&AudioParameterDescription) -> bool,
+    ) -> Option<AudioParameterDescription> {
+        let event_path = &self.instances[instance_id].event_path;
+        let described = self
+            .event_parameters
+            .get(event_path)
+            .and_then(|ps| ps.iter().find(|p| matches(p)))
+            .cloned();
+        // FMOD Studio gives every snapshot an intensity parameter
+        described.or_else(|| {
+            Some(snapshot_intensity_parameter())
+                .filter(|p| event_path.starts_with("snapshot:/") && matches(p))
+        })
+    }
+}
+
+fn snapshot_intensity_parameter() -> AudioParameterDescription {
+    AudioParameterDescription {
+        name: "Intensity".to_owned(),
+        id: AudioParameterId { data1: 0, data2: 0 },
+        minimum: 0.0,
+        maximum: 100.0,
+        default_value: 100.0,
+        kind: AudioParameterKind::GameControlled,
+        flags: AudioParameterFlags::default(),
+        labels: Vec::new(),
+    }
+}
+
+/// Like FMOD's, handles to banks (and the VCAs in them) become invalid once the bank has been
+/// unloaded.
+fn unloaded_bank_error(filename: &str) -> AudioError {
+    AudioError::from_fmod_code(
+        FMOD_ERR_INVALID_HANDLE,
+        format!("null audio backend: bank not loaded: {filename}"),
+    )
+}
+
+fn no_listener(index: usize) -> AudioError {
+    AudioError::InvalidParameter {
+        message: format!("null audio backend: no listener {index}"),
+        code: None,
+    }
+}
+
+/// FMOD refuses to let the game set automatic and read-only parameters.
+fn check_settable(description: &AudioParameterDescription) -> AudioResult<()> {
+    if description.flags.read_only || description.kind != AudioParameterKind::GameControlled {
+        return Err(AudioError::from_fmod_code(
+            FMOD_ERR_INVALID_PARAM,
+            format!(
+                "null audio backend: parameter {} can't be set by the game",
+                description.name
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// A snapshot of a bank known to the null backend.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NullBankRecord {
+    pub filename: String,
+    pub event_paths: Vec<String>,
+    /// VCAs in the bank, as given to [NullAudioBackend::add_vca].
+    pub vca_paths: Vec<String>,
+    /// Banks added with [NullAudioBackend::add_unloaded_bank] start off unloaded, and banks being
+    /// loaded via [AudioBackend::load_bank] finish loading on the next update.
+    pub loading_state: AudioLoadingState,
+    /// Number of outstanding `load_sample_data` calls, since FMOD reference counts these.
+    pub sample_data_refs: u32,
+}
+
+impl NullBankRecord {
+    pub fn is_loaded(&self) -> bool {
+        self.loading_state == AudioLoadingState::Loaded
+    }
+}
+
+/// A snapshot of an event instance created through the null backend.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NullInstanceRecord {
+    /// Index of this instance in creation order.
+    pub id: usize,
+    pub event_path: String,
+    pub playback_state: AudioPlaybackState,
+    /// As last set with [AudioEventInstance::set_3d_attributes].
+    pub position: Vec2,
+    pub velocity: Vec2,
+    /// As last set through either the 2D or 3D API, in world coordinates.
+    pub attributes: Audio3dAttributes,
+    /// Position of the instance at the time `start` was most recently called.
+    pub start_position: Option<Vec2>,
+    pub start_count: u32,
+    pub stop_count: u32,
+    /// Mode passed to the most recent `stop` call.
+    pub last_stop_mode: Option<AudioStopMode>,
+    pub released: bool,
+    /// Parameters which have been set (whether by name, ID or label), with their last value.
+    pub parameters: HashMap<String, f32>,
+    /// Labeled parameters set by name, with the last label they were set to.
+    pub parameter_labels: HashMap<String, String>,
+    pub callbacks_enabled: bool,
+    /// Callbacks which haven't been polled yet.
+    pub pending_callbacks: Vec<AudioEventCallback>,
+}
+
+impl NullInstanceRecord {
+    fn stop(&mut self, mode: AudioStopMode) {
+        if self.playback_state != AudioPlaybackState::Stopped {
+            match mode {
+                AudioStopMode::AllowFadeout => {
+                    self.playback_state = AudioPlaybackState::Stopping;
+                }
+                AudioStopMode::Immediate => {
+                    self.playback_state = AudioPlaybackState::Stopped;
+                    self.fire_callback(AudioEventCallback::Stopped);
+                }
+            }
+        }
+        self.stop_count += 1;
+        self.last_stop_mode = Some(mode);
+    }
+
+    fn fire_callback(&mut self, callback: AudioEventCallback) {
+        if self.callbacks_enabled {
+            self.pending_callbacks.push(callback);
+        }
+    }
+}
+
+/// A snapshot of a mixer bus known to the null backend.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NullBusRecord {
+    pub path: String,
+    pub volume: f32,
+    pub mute: bool,
+    pub paused: bool,
+    /// Modes passed to each `stop_all_events` call, in order. Everything is routed into the master
+    /// bus, so stopping its events stops every instance, but the null backend doesn't know which
+    /// events are routed into other buses, so nothing else happens for them.
+    pub stop_all_events_calls: Vec<AudioStopMode>,
+    pub metering_enabled: bool,
+}
+
+pub struct NullAudioBackend {
+    state: Rc<RefCell<NullAudioState>>,
+}
+
+impl NullAudioBackend {
+    /// Create a backend which knows about the given events (e.g. `"event:/Weapons/Explosion"`),
+    /// all in a single loaded `Master.bank`; looking up any other event is an error, as it would be
+    /// with FMOD. Like the real backends, the master bank's sample data starts off loaded. Only
+    /// the master bus (`bus:/`) exists to start with.
+    pub fn new(event_paths: &[&str]) -> Self {
+        let backend = NullAudioBackend {
+            state: Rc::new(RefCell::new(NullAudioState::default())),
+        };
+        backend.add_bank("Master.bank", event_paths);
+        backend.state.borrow_mut().banks[0].sample_data_refs = 1;
+        backend.add_bus("bus:/");
+        backend
+    }
+
+    /// Set the coordinates 2D listeners and positions are converted with, as passed to
+    /// [start_loading_audio_backend] for the real backends, which fail to load if it's invalid.
+    pub fn set_space_config(&self, space: AudioSpaceConfig) -> AudioResult<()> {
+        space.validate()?;
+        self.state.borrow_mut().space = space;
+        Ok(())
+    }
+
+    /// Add a VCA (e.g. `vca:/Music`) to a previously added bank. Like FMOD, it can only be looked up
+    /// while the bank is loaded.
+    pub fn add_vca(&self, bank_filename: &str, path: &str) {
+        let mut state = self.state.borrow_mut();
+        let bank = state
+            .banks
+            .iter_mut()
+            .find(|b| b.filename == bank_filename)
+            .expect("VCAs can only be added to banks which have already been added");
+        bank.vca_paths.push(path.to_owned());
+    }
+
+    /// Add a mixer bus, e.g. `bus:/SFX`.
+    pub fn add_bus(&self, path: &str) {
+        self.state.borrow_mut().buses.push(NullBusRecord {
+            path: path.to_owned(),
+            volume: 1.0,
+            mute: false,
+            paused: false,
+            stop_all_events_calls: Vec::new(),
+            metering_enabled: false,
+        });
+    }
+
+    /// Add another loaded bank containing the given events.
+    pub fn add_bank(&self, filename: &str, event_paths: &[&str]) {
+        self.push_bank(filename, event_paths, AudioLoadingState::Loaded);
+    }
+
+    /// Add a bank which isn't loaded yet, but can be loaded later via [AudioBackend::load_bank].
+    pub fn add_unloaded_bank(&self, filename: &str, event_paths: &[&str]) {
+        self.push_bank(filename, event_paths, AudioLoadingState::Unloaded);
+    }
+
+    fn push_bank(&self, filename: &str, event_paths: &[&str], loading_state: AudioLoadingState) {
+        self.state.borrow_mut().banks.push(NullBankRecord {
+            filename: filename.to_owned(),
+            event_paths: event_paths.iter().map(|s| s.to_string()).collect(),
+            vca_paths: Vec::new(),
+            loading_state,
+            sample_data_refs: 0,
+        });
+    }
+
+    /// Describe a parameter used by an event, so that it's returned by
+    /// [AudioEventDescription::parameters] and instances report its default value until it's set.
+    /// Instances can only use described parameters, apart from snapshots' `Intensity`.
+    pub fn add_parameter(&self, event_path: &str, description: AudioParameterDescription) {
+        self.state
+            .borrow_mut()
+            .event_parameters
+            .entry(event_path.to_owned())
+            .or_default()
+            .push(description);
+    }
+
+    /// Describe a global parameter, so that it's returned by [AudioBackend::global_parameters] and
+    /// can be looked up with [AudioBackend::get_parameter_handle]. Only described global
+    /// parameters can be set.
+    pub fn add_global_parameter(&self, description: AudioParameterDescription) {
+        self.state.borrow_mut().global_parameters.push(description);
+    }
+
+    /// Fire a callback on an instance (by [NullInstanceRecord::id]), e.g. a timeline marker, as if
+    /// FMOD had reached it during the last update. Lifecycle callbacks like
+    /// [AudioEventCallback::Started] are fired by the backend itself.
+    pub fn fire_callback(&self, instance_id: usize, callback: AudioEventCallback) {
+        self.state
+            .borrow_mut()
+            .instance_mut(instance_id)
+            .fire_callback(callback);
+    }
+
+    /// Get a handle for inspecting what the game asked this backend to do. The inspector stays valid
+    /// after the backend has been boxed up or shut down.
+    pub fn inspector(&self) -> NullAudioInspector {
+        NullAudioInspector {
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl AudioBackend for NullAudioBackend {
+    fn shutdown(self: Box<Self>) -> AudioResult<()> {
+        self.state.borrow_mut().shut_down = true;
+        Ok(())
+    }
+
+    fn update(&self) -> AudioResult<()> {
+        let mut state = self.state.borrow_mut();
+        state.update_count += 1;
+        for instance in &mut state.instances {
+            match instance.playback_state {
+                AudioPlaybackState::Starting => {
+                    instance.playback_state = AudioPlaybackState::Playing;
+                    instance.fire_callback(AudioEventCallback::Starting);
+                    instance.fire_callback(AudioEventCallback::Started);
+                }
+                AudioPlaybackState::Stopping => {
+                    instance.playback_state = AudioPlaybackState::Stopped;
+                    instance.fire_callback(AudioEventCallback::Stopped);
+                }
+                _ => {}
+            }
+        }
+        for bank in &mut state.banks {
+            if bank.loading_state == AudioLoadingState::Loading {
+                bank.loading_state = AudioLoadingState::Loaded;
+            }
+        }
+        Ok(())
+    }
+
+    fn get_event(&self, event_name: &str) -> AudioResult<Box<dyn AudioEventDescription>> {
+        if !self.state.borrow().has_event(event_name) {
+            return Err(AudioError::EventNotFound {
+                message: format!("null audio backend: event not found: {event_name}"),
+                code: Some(FMOD_ERR_EVENT_NOTFOUND),
+            });
+        }
+
+        Ok(Box::new(NullEventDescription {
+            state: self.state.clone(),
+            path: event_name.to_owned(),
+        }))
+    }
+
+    fn get_event_list(&self) -> AudioResult<Vec<Box<dyn AudioEventDescription>>> {
+        let mut all_events = Vec::new();
+        for bank in self.get_bank_list()? {
+            all_events.extend(bank.get_event_list()?);
+        }
+        Ok(all_events)
+    }
+
+    fn get_bank(&self, filename: &str) -> AudioResult<Box<dyn AudioBank>> {
+        if self.state.borrow().bank(filename).is_err() {
+            return Err(AudioError::BankNotFound {
+                message: format!("null audio backend: bank not loaded: {filename}"),
+                code: None,
+            });
+        }
+        Ok(Box::new(NullBank {
+            state: self.state.clone(),
+            filename: filename.to_owned(),
+        }))
+    }
+
+    fn get_bank_list(&self) -> AudioResult<Vec<Box<dyn AudioBank>>> {
+        Ok(self
+            .state
+            .borrow()
+            .banks
+            .iter()
+            .filter(|b| b.is_loaded())
+            .map(|b| {
+                Box::new(NullBank {
+                    state: self.state.clone(),
+                    filename: b.filename.clone(),
+                }) as Box<dyn AudioBank>
+            })
+            .collect())
+    }
+
+    fn load_bank(&self, filename: &str) -> AudioResult<Box<dyn AudioBankLoader>> {
+        let mut state = self.state.borrow_mut();
+        match state.banks.iter_mut().find(|b| b.filename == filename) {
+            Some(bank) if bank.loading_state == AudioLoadingState::Unloaded => {
+                bank.loading_state = AudioLoadingState::Loading;
+            }
+            Some(_) => {
+                return Err(AudioError::from_fmod_code(
+                    FMOD_ERR_EVENT_ALREADY_LOADED,
+                    format!("null audio backend: bank already loaded: {filename}"),
+                ));
+            }
+            None => {
+                return Err(AudioError::from_fmod_code(
+                    FMOD_ERR_FILE_NOTFOUND,
+                    format!("null audio backend: bank not found: {filename}"),
+                ));
+            }
+        }
+
+        Ok(Box::new(NullBankLoader {
+            state: self.state.clone(),
+            filename: filename.to_owned(),
+        }))
+    }
+
+    fn load_bank_from_memory(&self, name: &str, _data: &[u8]) -> AudioResult<Box<dyn AudioBank>> {
+        // we can't read events out of the data, so use the ones from a bank registered with
+        // add_unloaded_bank if there is one
+        let mut state = self.state.borrow_mut();
+        match state.banks.iter_mut().find(|b| b.filename == name) {
+            Some(bank) if bank.loading_state == AudioLoadingState::Unloaded => {
+                bank.loading_state = AudioLoadingState::Loaded;
+            }
+            Some(_) => {
+                return Err(AudioError::from_fmod_code(
+                    FMOD_ERR_EVENT_ALREADY_LOADED,
+                    format!("null audio backend: bank already loaded: {name}"),
+                ));
+            }
+            None => state.banks.push(NullBankRecord {
+                filename: name.to_owned(),
+                event_paths: Vec::new(),
+                vca_paths: Vec::new(),
+                loading_state: AudioLoadingState::Loaded,
+                sample_data_refs: 0,
+            }),
+        }
+
+        Ok(Box::new(NullBank {
+            state: self.state.clone(),
+            filename: name.to_owned(),
+        }))
+    }
+
+    fn load_bank_from_source(
+        &self,
+        name: &str,
+        source: Arc<dyn AudioAssetSource>,
+    ) -> AudioResult<Box<dyn AudioBank>> {
+        // read the whole asset so that misbehaving sources still show up as errors
+        let mut stream = source.open(name)?;
+        let mut data = vec![0; stream.size() as usize];
+        let mut total = 0;
+        while total < data.len() {
+            let read = stream.read(&mut data[total..])?;
+            if read == 0 {
+                return Err(AudioError::from_fmod_code(
+                    FMOD_ERR_FILE_BAD,
+                    format!("null audio backend: asset {name} ended after {total} bytes"),
+                ));
+            }
+            total += read;
+        }
+
+        self.load_bank_from_memory(name, &data)
+    }
+
+    fn unload_bank(&self, filename: &str) -> AudioResult<()> {
+        self.get_bank(filename)?.unload()
+    }
+
+    fn get_bus(&self, path: &str) -> AudioResult<Box<dyn AudioBus>> {
+        self.state.borrow_mut().bus_mut(path)?;
+        Ok(Box::new(NullBus {
+            state: self.state.clone(),
+            path: path.to_owned(),
+        }))
+    }
+
+    fn get_vca(&self, path: &str) -> AudioResult<Box<dyn AudioVca>> {
+        if !self.state.borrow().has_vca(path) {
+            return Err(AudioError::from_fmod_code(
+                FMOD_ERR_EVENT_NOTFOUND,
+                format!("null audio backend: VCA not found: {path}"),
+            ));
+        }
+
+        Ok(Box::new(NullVca {
+            state: self.state.clone(),
+            path: path.to_owned(),
+        }))
+    }
+
+    fn vca_list(&self) -> AudioResult<Vec<Box<dyn AudioVca>>> {
+        Ok(self
+            .state
+            .borrow()
+            .banks
+            .iter()
+            .filter(|b| b.is_loaded())
+            .flat_map(|b| &b.vca_paths)
+            .map(|path| {
+                Box::new(NullVca {
+                    state: self.state.clone(),
+                    path: path.clone(),
+                }) as Box<dyn AudioVca>
+            })
+            .collect())
+    }
+
+    fn set_listeners(&self, listeners: &[AudioListener]) -> AudioResult<()> {
+        let space = self.state.borrow().space;
+        let listeners: Vec<_> = listeners.iter().map(|l| l.to_3d(&space)).collect();
+        self.set_listeners_3d(&listeners)
+    }
+
+    fn set_listeners_3d(&self, listeners: &[AudioListener3d]) -> AudioResult<()> {
+        if listeners.is_empty() {
+            return Err(AudioError::InvalidParameter {
+                message: "null audio backend: at least one listener is needed".to_owned(),
+                code: None,
+            });
+        }
+        // like FMOD, ignore listeners past the maximum
+        let listeners = &listeners[..listeners.len().min(MAX_LISTENERS)];
+        self.state.borrow_mut().listeners = listeners.to_vec();
+        Ok(())
+    }
+
+    fn set_listener_weight(&self, index: usize, weight: f32) -> AudioResult<()> {
+        if !(0.0..=1.0).contains(&weight) {
+            return Err(AudioError::from_fmod_code(
+                FMOD_ERR_INVALID_PARAM,
+                format!("null audio backend: listener weight out of range: {weight}"),
+            ));
+        }
+        let mut state = self.state.borrow_mut();
+        let Some(listener) = state.listeners.get_mut(index) else {
+            return Err(no_listener(index));
+        };
+        listener.weight = weight;
+        Ok(())
+    }
+
+    fn remove_listener(&self, index: usize) -> AudioResult<()> {
+        let mut state = self.state.borrow_mut();
+        if index >= state.listeners.len() {
+            return Err(no_listener(index));
+        }
+        if state.listeners.len() == 1 {
+            return Err(AudioError::InvalidParameter {
+                message: "null audio backend: at least one listener is needed".to_owned(),
+                code: None,
+            });
+        }
+        state.listeners.remove(index);
+        Ok(())
+    }
+
+    fn set_3d_settings(
+        &self,
+        doppler_scale: f32,
+        distance_factor: f32,
+        rolloff_scale: f32,
+    ) -> AudioResult<()> {
+        // FMOD needs a positive distance factor, and the scales can't be negative
+        let valid = |scale: f32| scale.is_finite() && scale >= 0.0;
+        if !(valid(doppler_scale)
+            && valid(rolloff_scale)
+            && valid(distance_factor)
+            && distance_factor > 0.0)
+        {
+            return Err(AudioError::from_fmod_code(
+                FMOD_ERR_INVALID_PARAM,
+                format!(
+                    "null audio backend: invalid 3D settings \
+                     {doppler_scale}, {distance_factor}, {rolloff_scale}"
+                ),
+            ));
+        }
+        self.state.borrow_mut().settings_3d = Some([doppler_scale, distance_factor, rolloff_scale]);
+        Ok(())
+    }
+
+    fn set_parameter_by_name(&self, name: &str, value: f32) -> AudioResult<()> {
+        let mut state = self.state.borrow_mut();
+        match state.global_parameters.iter().find(|p| p.name == name) {
+            Some(description) => check_settable(description)?,
+            None => {
+                return Err(AudioError::from_fmod_code(
+                    FMOD_ERR_EVENT_NOTFOUND,
+                    format!("null audio backend: global parameter not found: {name}"),
+                ));
+            }
+        }
+        state.parameters.insert(name.to_owned(), value);
+        Ok(())
+    }
+
+    fn global_parameters(&self) -> AudioResult<Vec<AudioParameterDescription>> {
+        Ok(self.state.borrow().global_parameters.clone())
+    }
+
+    fn get_parameter_handle(&self, name: &str) -> AudioResult<ParameterHandle> {
+        match self
+            .state
+            .borrow()
+            .global_parameters
+            .iter()
+            .find(|p| p.name == name)
+        {
+            Some(description) => Ok(ParameterHandle::from(description)),
+            None => Err(AudioError::from_fmod_code(
+                FMOD_ERR_EVENT_NOTFOUND,
+                format!("null audio backend: global parameter not found: {name}"),
+            )),
+        }
+    }
+
+    fn set_parameter(&self, handle: &ParameterHandle, value: f32) -> AudioResult<()> {
+        self.set_parameter_by_name(handle.name(), value)
+    }
+}
+
+struct NullBankLoader {
+    state: Rc<RefCell<NullAudioState>>,
+    filename: String,
+}
+
+impl AudioBankLoader for NullBankLoader {
+    fn get_progress(&self) -> f32 {
+        // failed loads have finished too
+        match self.get_loaded() {
+            Some(_) => 1.0,
+            None => 0.0,
+        }
+    }
+
+    fn get_loaded(&self) -> Option<AudioResult<Box<dyn AudioBank>>> {
+        let state = self.state.borrow();
+        match state.banks.iter().find(|b| b.filename == self.filename) {
+            Some(bank) if bank.loading_state == AudioLoadingState::Loading => None,
+            Some(bank) if bank.is_loaded() => Some(Ok(Box::new(NullBank {
+                state: self.state.clone(),
+                filename: self.filename.clone(),
+            }))),
+            _ => Some(Err(AudioError::other(format!(
+                "null audio backend: bank {} was unloaded while loading",
+                self.filename
+            )))),
+        }
+    }
+}
+
+struct NullBank {
+    state: Rc<RefCell<NullAudioState>>,
+    filename: String,
+}
+
+impl AudioBank for NullBank {
+    fn get_filename(&self) -> String {
+        self.filename.clone()
+    }
+
+    fn get_path(&self) -> AudioResult<String> {
+        self.state.borrow().bank(&self.filename)?;
+        Ok(format!("bank:/{}", self.filename.trim_end_matches(".bank")))
+    }
+
+    fn get_id(&self) -> AudioResult<String> {
+        let state = self.state.borrow();
+        state.bank(&self.filename)?;
+        let index = state
+            .banks
+            .iter()
+            .position(|b| b.is_loaded() && b.filename == self.filename)
+            .expect("bank was just found");
+        Ok(format!("{{00000000-0000-0000-0000-{index:0>12x}}}"))
+    }
+
+    fn get_loading_state(&self) -> AudioResult<AudioLoadingState> {
+        // like FMOD's bank handles, this one becomes invalid once the bank has been unloaded
+        Ok(self.state.borrow().bank(&self.filename)?.loading_state)
+    }
+
+    fn get_sample_loading_state(&self) -> AudioResult<AudioLoadingState> {
+        Ok(
+            if self.state.borrow().bank(&self.filename)?.sample_data_refs > 0 {
+                AudioLoadingState::Loaded
+            } else {
+                AudioLoadingState::Unloaded
+            },
+        )
+    }
+
+    fn load_sample_data(&self) -> AudioResult<()> {
+        self.state
+            .borrow_mut()
+            .bank_mut(&self.filename)?
+            .sample_data_refs += 1;
+        Ok(())
+    }
+
+    fn unload_sample_data(&self) -> AudioResult<()> {
+        let mut state = self.state.borrow_mut();
+        let bank = state.bank_mut(&self.filename)?;
+        bank.sample_data_refs = bank.sample_data_refs.saturating_sub(1);
+        Ok(())
+    }
+
+    fn get_event_list(&self) -> AudioResult<Vec<Box<dyn AudioEventDescription>>> {
+        Ok(self
+            .state
+            .borrow()
+            .bank(&self.filename)?
+            .event_paths
+            .iter()
+            .map(|path| {
+                Box::new(NullEventDescription {
+                    state: self.state.clone(),
+                    path: path.clone(),
+                }) as Box<dyn AudioEventDescription>
+            })
+            .collect())
+    }
+
+    fn unload(self: Box<Self>) -> AudioResult<()> {
+        let mut state = self.state.borrow_mut();
+        let bank = state.bank_mut(&self.filename)?;
+        bank.loading_state = AudioLoadingState::Unloaded;
+        bank.sample_data_refs = 0;
+        Ok(())
+    }
+}
+
+struct NullEventDescription {
+    state: Rc<RefCell<NullAudioState>>,
+    path: String,
+}
+
+impl AudioEventDescription for NullEventDescription {
+    fn create_instance(&self) -> AudioResult<Box<dyn AudioEventInstance>> {
+        let mut state = self.state.borrow_mut();
+        if !state.has_event(&self.path) {
+            return Err(AudioError::from_fmod_code(
+                FMOD_ERR_INVALID_HANDLE,
+                format!(
+                    "null audio backend: event's bank has been unloaded: {}",
+                    self.path
+                ),
+            ));
+        }
+        let id = state.instances.len();
+        let attributes = state.space.attributes_from_2d(Vec2::ZERO, Vec2::ZERO);
+        state.instances.push(NullInstanceRecord {
+            id,
+            event_path: self.path.clone(),
+            playback_state: AudioPlaybackState::Stopped,
+            position: Vec2::ZERO,
+            velocity: Vec2::ZERO,
+            attributes,
+            start_position: None,
+            start_count: 0,
+            stop_count: 0,
+            last_stop_mode: None,
+            released: false,
+            parameters: HashMap::new(),
+            parameter_labels: HashMap::new(),
+            callbacks_enabled: false,
+            pending_callbacks: Vec::new(),
+        });
+
+        Ok(Box::new(NullEventInstance {
+            state: self.state.clone(),
+            id,
+        }))
+    }
+
+    fn get_path(&self) -> AudioResult<String> {
+        Ok(self.path.clone())
+    }
+
+    fn parameters(&self) -> AudioResult<Vec<AudioParameterDescription>> {
+        Ok(self
+            .state
+            .borrow()
+            .event_parameters
+            .get(&self.path)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    fn is_snapshot(&self) -> AudioResult<bool> {
+        Ok(self.path.starts_with("snapshot:/"))
+    }
+}
+
+struct NullEventInstance {
+    state: Rc<RefCell<NullAudioState>>,
+    id: usize,
+}
+
+impl AudioEventInstance for NullEventInstance {
+    fn release(self: Box<Self>) -> AudioResult<()> {
+        // like FMOD, releasing doesn't stop the instance - it just gets cleaned up once it stops
+        self.state.borrow_mut().instance_mut(self.id).released = true;
+        Ok(())
+    }
+
+    fn start(&self) -> AudioResult<()> {
+        let mut state = self.state.borrow_mut();
+        let instance = state.instance_mut(self.id);
+        instance.playback_state = AudioPlaybackState::Starting;
+        instance.start_position = Some(instance.position);
+        instance.start_count += 1;
+        Ok(())
+    }
+
+    fn stop(&self, mode: AudioStopMode) -> AudioResult<()> {
+        self.state.borrow_mut().instance_mut(self.id).stop(mode);
+        Ok(())
+    }
+
+    fn set_3d_attributes(&self, position: Vec2, velocity: Vec2) -> AudioResult<()> {
+        let mut state = self.state.borrow_mut();
+        let attributes = state.space.attributes_from_2d(position, velocity);
+        let instance = state.instance_mut(self.id);
+        instance.position = position;
+        instance.velocity = velocity;
+        instance.attributes = attributes;
+        Ok(())
+    }
+
+    fn set_attributes_3d(&self, attributes: &Audio3dAttributes) -> AudioResult<()> {
+        self.state.borrow_mut().instance_mut(self.id).attributes = *attributes;
+        Ok(())
+    }
+
+    fn get_playback_state(&self) -> AudioResult<AudioPlaybackState> {
+        Ok(self.state.borrow().instances[self.id].playback_state)
+    }
+
+    fn set_parameter_by_name(&self, name: &str, value: f32) -> AudioResult<()> {
+        let mut state = self.state.borrow_mut();
+        let description = state.parameter_by_name(self.id, name)?;
+        check_settable(&description)?;
+        state
+            .instance_mut(self.id)
+            .parameters
+            .insert(description.name, value);
+        Ok(())
+    }
+
+    fn set_parameter_by_id(&self, id: AudioParameterId, value: f32) -> AudioResult<()> {
+        let mut state = self.state.borrow_mut();
+        let description = state.parameter_by_id(self.id, id)?;
+        check_settable(&description)?;
+        state
+            .instance_mut(self.id)
+            .parameters
+            .insert(description.name, value);
+        Ok(())
+    }
+
+    fn set_parameter_by_name_with_label(&self, name: &str, label: &str) -> AudioResult<()> {
+        let mut state = self.state.borrow_mut();
+        let description = state.parameter_by_name(self.id, name)?;
+        check_settable(&description)?;
+        let Some(index) = description.labels.iter().position(|l| l == label) else {
+            return Err(AudioError::from_fmod_code(
+                FMOD_ERR_EVENT_NOTFOUND,
+                format!("null audio backend: parameter {name} has no label {label}"),
+            ));
+        };
+        let instance = state.instance_mut(self.id);
+        instance
+            .parameters
+            .insert(name.to_owned(), description.minimum + index as f32);
+        instance
+            .parameter_labels
+            .insert(name.to_owned(), label.to_owned());
+        Ok(())
+    }
+
+    fn get_parameter_by_name(&self, name: &str) -> AudioResult<AudioParameterValue> {
+        let state = self.state.borrow();
+        let description = state.parameter_by_name(self.id, name)?;
+        let value = state.instances[self.id]
+            .parameters
+            .get(name)
+            .copied()
+            .unwrap_or(description.default_value);
+        // there's no automation or modulation here, so the final value is just the value
+        Ok(AudioParameterValue {
+            value,
+            final_value: value,
+        })
+    }
+
+    fn enable_callbacks(&self) -> AudioResult<()> {
+        self.state
+            .borrow_mut()
+            .instance_mut(self.id)
+            .callbacks_enabled = true;
+        Ok(())
+    }
+
+    fn poll_callbacks(&self) -> AudioResult<Vec<AudioEventCallback>> {
+        let mut state = self.state.borrow_mut();
+        let instance = state.instance_mut(self.id);
+        if !instance.callbacks_enabled {
+            return Err(AudioError::other(
+                "null audio backend: callbacks haven't been enabled for this event instance",
+            ));
+        }
+        Ok(std::mem::take(&mut instance.pending_callbacks))
+    }
+}
+
+struct NullBus {
+    state: Rc<RefCell<NullAudioState>>,
+    path: String,
+}
+
+impl NullBus {
+    fn with_bus<T>(&self, f: impl FnOnce(&mut NullBusRecord) -> T) -> AudioResult<T> {
+        Ok(f(self.state.borrow_mut().bus_mut(&self.path)?))
+    }
+}
+
+impl AudioBus for NullBus {
+    fn get_path(&self) -> AudioResult<String> {
+        Ok(self.path.clone())
+    }
+
+    fn set_volume(&self, volume: f32) -> AudioResult<()> {
+        self.with_bus(|bus| bus.volume = volume)
+    }
+
+    fn get_volume(&self) -> AudioResult<AudioVolume> {
+        // nothing else affects the volume, so the final volume is just the volume
+        self.with_bus(|bus| AudioVolume {
+            volume: bus.volume,
+            final_volume: bus.volume,
+        })
+    }
+
+    fn set_mute(&self, mute: bool) -> AudioResult<()> {
+        self.with_bus(|bus| bus.mute = mute)
+    }
+
+    fn get_mute(&self) -> AudioResult<bool> {
+        self.with_bus(|bus| bus.mute)
+    }
+
+    fn set_paused(&self, paused: bool) -> AudioResult<()> {
+        self.with_bus(|bus| bus.paused = paused)
+    }
+
+    fn get_paused(&self) -> AudioResult<bool> {
+        self.with_bus(|bus| bus.paused)
+    }
+
+    fn stop_all_events(&self, mode: AudioStopMode) -> AudioResult<()> {
+        let mut state = self.state.borrow_mut();
+        state.bus_mut(&self.path)?.stop_all_events_calls.push(mode);
+        if self.path == "bus:/" {
+            for instance in &mut state.instances {
+                if instance.playback_state != AudioPlaybackState::Stopped {
+                    instance.stop(mode);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn enable_metering(&self) -> AudioResult<()> {
+        self.with_bus(|bus| bus.metering_enabled = true)
+    }
+
+    fn get_metering(&self) -> AudioResult<Option<AudioMeteringInfo>> {
+        // nothing is ever playing, so there are no levels
+        self.with_bus(|bus| bus.metering_enabled.then(AudioMeteringInfo::default))
+    }
+}
+
+struct NullVca {
+    state: Rc<RefCell<NullAudioState>>,
+    path: String,
+}
+
+impl AudioVca for NullVca {
+    fn get_path(&self) -> AudioResult<String> {
+        Ok(self.path.clone())
+    }
+
+    fn set_volume(&self, volume: f32) -> AudioResult<()> {
+        let mut state = self.state.borrow_mut();
+        if !state.has_vca(&self.path) {
+            return Err(AudioError::from_fmod_code(
+                FMOD_ERR_INVALID_HANDLE,
+                format!("null audio backend: VCA not loaded: {}", self.path),
+            ));
+        }
+        state.vca_volumes.insert(self.path.clone(), volume);
+        Ok(())
+    }
+
+    fn get_volume(&self) -> AudioResult<AudioVolume> {
+        let state = self.state.borrow();
+        if !state.has_vca(&self.path) {
+            return Err(AudioError::from_fmod_code(
+                FMOD_ERR_INVALID_HANDLE,
+                format!("null audio backend: VCA not loaded: {}", self.path),
+            ));
+        }
+        let volume = state.vca_volumes.get(&self.path).copied().unwrap_or(1.0);
+        Ok(AudioVolume {
+            volume,
+            final_volume: volume,
+        })
+    }
+}
+
+/// Read-only view of everything a [NullAudioBackend] has been asked to do.
+#[derive(Clone)]
+pub struct NullAudioInspector {
+    state: Rc<RefCell<NullAudioState>>,
+}
+
+impl NullAudioInspector {
+    /// All instances created so far, in creation order (released instances included).
+    pub fn instances(&self) -> Vec<NullInstanceRecord> {
+        self.state.borrow().instances.clone()
+    }
+
+    /// All instances created so far of the given event.
+    pub fn instances_of(&self, event_path: &str) -> Vec<NullInstanceRecord> {
+        self.state
+            .borrow()
+            .instances
+            .iter()
+            .filter(|i| i.event_path == event_path)
+            .cloned()
+            .collect()
+    }
+
+    /// Whether any instance of the given event was started while positioned at `position`.
+    pub fn was_started_at(&self, event_path: &str, position: Vec2) -> bool {
+        self.state
+            .borrow()
+            .instances
+            .iter()
+            .any(|i| i.event_path == event_path && i.start_position == Some(position))
+    }
+
+    /// Number of instances of the given event that are currently starting or playing.
+    pub fn playing_count(&self, event_path: &str) -> usize {
+        self.state
+            .borrow()
+            .instances
+            .iter()
+            .filter(|i| {
+                i.event_path == event_path
+                    && matches!(
+                        i.playback_state,
+                        AudioPlaybackState::Starting
+                            | AudioPlaybackState::Playing
+                            | AudioPlaybackState::Sustaining
+                    )
+            })
+            .count()
+    }
+
+    /// All banks the backend knows about, including ones which have been unloaded.
+    pub fn banks(&self) -> Vec<NullBankRecord> {
+        self.state.borrow().banks.clone()
+    }
+
+    pub fn buses(&self) -> Vec<NullBusRecord> {
+        self.state.borrow().buses.clone()
+    }
+
+    /// The last volume set for a VCA, if it has been set at all.
+    pub fn vca_volume(&self, path: &str) -> Option<f32> {
+        self.state.borrow().vca_volumes.get(path).copied()
+    }
+
+    pub fn listeners(&self) -> Vec<AudioListener3d> {
+        self.state.borrow().listeners.clone()
+    }
+
+    /// The doppler scale, distance factor and rolloff scale last given to
+    /// [AudioBackend::set_3d_settings], or `None` if they're still FMOD's defaults.
+    pub fn settings_3d(&self) -> Option<[f32; 3]> {
+        self.state.borrow().settings_3d
+    }
+
+    /// The last value set for a global parameter, if it has been set at all.
+    pub fn parameter(&self, name: &str) -> Option<f32> {
+        self.state.borrow().parameters.get(name).copied()
+    }
+
+    /// Number of times [AudioBackend::update] has been called.
+    pub fn update_count(&self) -> u64 {
+        self.state.borrow().update_count
+    }
+
+    pub fn is_shut_down(&self) -> bool {
+        self.state.borrow().shut_down
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::backend_recording::RecordingAudioBackend;
+    use super::conformance::{self, ConformanceFixture};
+    use super::snapshot::{AudioSnapshot, AudioSnapshotStack};
+    use super::*;
+
+    const EVENT: &str = "event:/Music/Level 01";
+
+    /// A null backend with the same contents as FMOD's Studio examples, as far as the conformance
+    /// suite is concerned.
+    fn fmod_examples_backend(fixture: &ConformanceFixture) -> NullAudioBackend {
+        let backend = NullAudioBackend::new(&[]);
+        backend.add_bank("Master.strings.bank", &[]);
+        backend.add_bank(
+            fixture.looping_event_bank,
+            &[fixture.looping_event, fixture.parameter_event],
+        );
+        backend.add_unloaded_bank(fixture.unloaded_bank, &[fixture.unloaded_bank_event]);
+        backend.add_parameter(
+            fixture.parameter_event,
+            AudioParameterDescription {
+                name: fixture.parameter.to_owned(),
+                id: AudioParameterId { data1: 1, data2: 2 },
+                minimum: 0.0,
+                maximum: 3.0,
+                default_value: 0.0,
+                kind: AudioParameterKind::GameControlled,
+                flags: AudioParameterFlags {
+                    discrete: true,
+                    ..Default::default()
+                },
+                labels: Vec::new(),
+            },
+        );
+        backend
+    }
+
+    #[test]
+    fn null_backend_conforms() {
+        let fixture = ConformanceFixture::fmod_examples(Vec::new());
+        let backend = fmod_examples_backend(&fixture);
+        conformance::check_backend(Box::new(backend), &fixture).unwrap();
+    }
+
+    #[test]
+    fn recording_backend_conforms() {
+        let fixture = ConformanceFixture::fmod_examples(Vec::new());
+        let backend =
+            RecordingAudioBackend::new(Box::new(fmod_examples_backend(&fixture)), std::io::sink());
+        conformance::check_backend(Box::new(backend), &fixture).unwrap();
+    }
+
+    #[test]
+    fn unload_by_name_after_reloading_under_same_name() {
+        let backend = NullAudioBackend::new(&[]);
+        backend.add_unloaded_bank("Level.bank", &[EVENT]);
+        let inspector = backend.inspector();
+
+        let bank = backend.load_bank_from_memory("Level.bank", &[]).unwrap();
+        bank.unload().unwrap();
+        backend.load_bank_from_memory("Level.bank", &[]).unwrap();
+        backend.unload_bank("Level.bank").unwrap();
+
+        assert!(backend.get_event(EVENT).is_err());
+        assert!(
+            inspector
+                .banks()
+                .iter()
+                .all(|b| b.filename != "Level.bank" || !b.is_loaded())
+        );
+    }
+
+    #[test]
+    fn fadeout_stop_goes_through_stopping() {
+        let backend = NullAudioBackend::new(&[EVENT]);
+        let inspector = backend.inspector();
+        let instance = backend.get_event(EVENT).unwrap().create_instance().unwrap();
+        instance.start().unwrap();
+        backend.update().unwrap();
+
+        instance.stop(AudioStopMode::AllowFadeout).unwrap();
+        assert_eq!(
+            instance.get_playback_state().unwrap(),
+            AudioPlaybackState::Stopping
+        );
+        assert_eq!(
+            inspector.instances()[0].last_stop_mode,
+            Some(AudioStopMode::AllowFadeout)
+        );
+    }
+
+    #[test]
+    fn immediate_stop_skips_stopping() {
+        let backend = NullAudioBackend::new(&[EVENT]);
+        let inspector = backend.inspector();
+        let instance = backend.get_event(EVENT).unwrap().create_instance().unwrap();
+        instance.start().unwrap();
+        backend.update().unwrap();
+
+        instance.stop(AudioStopMode::Immediate).unwrap();
+        assert_eq!(
+            instance.get_playback_state().unwrap(),
+            AudioPlaybackState::Stopped
+        );
+        assert_eq!(
+            inspector.instances()[0].last_stop_mode,
+            Some(AudioStopMode::Immediate)
+        );
+    }
+
+    #[test]
+    fn start_goes_through_starting_to_playing() {
+        let backend = NullAudioBackend::new(&[EVENT]);
+        let inspector = backend.inspector();
+        let instance = backend.get_event(EVENT).unwrap().create_instance().unwrap();
+        assert_eq!(
+            instance.get_playback_state().unwrap(),
+            AudioPlaybackState::Stopped
+        );
+
+        instance.start().unwrap();
+        assert_eq!(
+            instance.get_playback_state().unwrap(),
+            AudioPlaybackState::Starting
+        );
+        assert_eq!(inspector.playing_count(EVENT), 1);
+
+        backend.update().unwrap();
+        assert_eq!(
+            instance.get_playback_state().unwrap(),
+            AudioPlaybackState::Playing
+        );
+        assert_eq!(inspector.update_count(), 1);
+        assert_eq!(inspector.instances()[0].start_count, 1);
+    }
+
+    #[test]
+    fn inspector_sees_where_events_were_started() {
+        let backend = NullAudioBackend::new(&[EVENT]);
+        let inspector = backend.inspector();
+        let instance = backend.get_event(EVENT).unwrap().create_instance().unwrap();
+        instance
+            .set_3d_attributes(Vec2::new(3.0, 4.0), Vec2::ZERO)
+            .unwrap();
+        instance.start().unwrap();
+        // moving the instance afterwards doesn't change where it was started
+        instance
+            .set_3d_attributes(Vec2::new(5.0, 6.0), Vec2::new(1.0, 0.0))
+            .unwrap();
+
+        assert!(inspector.was_started_at(EVENT, Vec2::new(3.0, 4.0)));
+        assert!(!inspector.was_started_at(EVENT, Vec2::new(5.0, 6.0)));
+        let record = &inspector.instances_of(EVENT)[0];
+        assert_eq!(record.position, Vec2::new(5.0, 6.0));
+        assert_eq!(record.velocity, Vec2::new(1.0, 0.0));
+        assert_eq!(record.attributes.position, Vec3::new(5.0, 6.0, 0.0));
+    }
+
+    #[test]
+    fn released_instances_keep_playing() {
+        let backend = NullAudioBackend::new(&[EVENT]);
+        let inspector = backend.inspector();
+        let instance = backend.get_event(EVENT).unwrap().create_instance().unwrap();
+        instance.start().unwrap();
+        instance.release().unwrap();
+        backend.update().unwrap();
+
+        let record = &inspector.instances()[0];
+        assert!(record.released);
+        assert_eq!(record.playback_state, AudioPlaybackState::Playing);
+    }
+
+    #[test]
+    fn unknown_events_are_not_found() {
+        let backend = NullAudioBackend::new(&[EVENT]);
+        assert!(matches!(
+            backend.get_event("event:/Missing"),
+            Err(AudioError::EventNotFound { .. })
+        ));
+        assert_eq!(backend.get_event_list().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn inspector_outlives_the_backend() {
+        let backend = NullAudioBackend::new(&[EVENT]);
+        let inspector = backend.inspector();
+        backend
+            .set_listeners(&[AudioListener {
+                weight: 1.0,
+                position: Vec2::new(1.0, 2.0),
+                velocity: Vec2::ZERO,
+                attenuation_position: None,
+            }])
+            .unwrap();
+        Box::new(backend).shutdown().unwrap();
+
+        assert!(inspector.is_shut_down());
+        assert_eq!(
+            inspector.listeners()[0].attributes.position,
+            Vec3::new(1.0, 2.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn banks_load_in_the_background() {
+        let backend = NullAudioBackend::new(&[]);
+        backend.add_unloaded_bank("Level.bank", &[EVENT]);
+        assert!(backend.get_event(EVENT).is_err());
+
+        let loader = backend.load_bank("Level.bank").unwrap();
+        assert!(loader.get_loaded().is_none());
+        assert_eq!(loader.get_progress(), 0.0);
+        assert!(matches!(
+            backend.get_bank("Level.bank"),
+            Err(AudioError::BankNotFound { .. })
+        ));
+
+        backend.update().unwrap();
+        let bank = loader.get_loaded().unwrap().unwrap();
+        assert_eq!(loader.get_progress(), 1.0);
+        assert_eq!(bank.get_filename(), "Level.bank");
+        assert_eq!(bank.get_path().unwrap(), "bank:/Level");
+        assert_eq!(bank.get_loading_state().unwrap(), AudioLoadingState::Loaded);
+        let events = bank.get_event_list().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].get_path().unwrap(), EVENT);
+        backend.get_event(EVENT).unwrap();
+    }
+
+    #[test]
+    fn loading_a_bank_twice_fails() {
+        let backend = NullAudioBackend::new(&[]);
+        assert!(matches!(
+            backend.load_bank("Master.bank"),
+            Err(AudioError::AlreadyLoaded { .. })
+        ));
+        assert!(backend.load_bank("Missing.bank").is_err());
+    }
+
+    #[test]
+    fn sample_data_is_reference_counted() {
+        let backend = NullAudioBackend::new(&[]);
+        backend.add_bank("Level.bank", &[EVENT]);
+        let bank = backend.get_bank("Level.bank").unwrap();
+        assert_eq!(
+            bank.get_sample_loading_state().unwrap(),
+            AudioLoadingState::Unloaded
+        );
+
+        bank.load_sample_data().unwrap();
+        bank.load_sample_data().unwrap();
+        bank.unload_sample_data().unwrap();
+        assert_eq!(
+            bank.get_sample_loading_state().unwrap(),
+            AudioLoadingState::Loaded
+        );
+        bank.unload_sample_data().unwrap();
+        assert_eq!(
+            bank.get_sample_loading_state().unwrap(),
+            AudioLoadingState::Unloaded
+        );
+    }
+
+    #[test]
+    fn unloading_a_bank_invalidates_its_handles_and_events() {
+        let backend = NullAudioBackend::new(&[]);
+        backend.add_bank("Level.bank", &[EVENT]);
+        let description = backend.get_event(EVENT).unwrap();
+        let bank = backend.get_bank("Level.bank").unwrap();
+
+        backend.unload_bank("Level.bank").unwrap();
+        assert!(matches!(
+            bank.get_loading_state(),
+            Err(AudioError::InvalidHandle { .. })
+        ));
+        assert!(description.create_instance().is_err());
+        assert!(backend.get_event(EVENT).is_err());
+        assert_eq!(backend.get_bank_list().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn banks_load_from_memory_straight_away() {
+        let backend = NullAudioBackend::new(&[]);
+        backend.add_unloaded_bank("Level.bank", &[EVENT]);
+
+        let bank = backend
+            .load_bank_from_memory("Level.bank", &[1, 2, 3])
+            .unwrap();
+        assert_eq!(bank.get_loading_state().unwrap(), AudioLoadingState::Loaded);
+        backend.get_event(EVENT).unwrap();
+        assert!(matches!(
+            backend.load_bank_from_memory("Level.bank", &[]),
+            Err(AudioError::AlreadyLoaded { .. })
+        ));
+
+        // banks which weren't added up front load too, just without any events
+        let unknown = backend.load_bank_from_memory("DLC.bank", &[]).unwrap();
+        assert!(unknown.get_event_list().unwrap().is_empty());
+        backend.get_bank("DLC.bank").unwrap();
+    }
+
+    /// Serves `data` as the asset `name`, while claiming it's `size` bytes long.
+    struct TestAssetSource {
+        name: &'static str,
+        data: Vec<u8>,
+        size: u64,
+    }
+
+    impl AudioAssetSource for TestAssetSource {
+        fn open(&self, name: &str) -> AudioResult<Box<dyn AudioAssetStream>> {
+            if name != self.name {
+                return Err(AudioError::other(format!("asset not found: {name}")));
+            }
+            Ok(Box::new(TestAssetStream {
+                data: self.data.clone(),
+                size: self.size,
+                position: 0,
+            }))
+        }
+    }
+
+    struct TestAssetStream {
+        data: Vec<u8>,
+        size: u64,
+        position: usize,
+    }
+
+    impl AudioAssetStream for TestAssetStream {
+        fn size(&self) -> u64 {
+            self.size
+        }
+
+        fn read(&mut self, buffer: &mut [u8]) -> AudioResult<usize> {
+            let remaining = &self.data[self.position..];
+            let len = remaining.len().min(buffer.len());
+            buffer[..len].copy_from_slice(&remaining[..len]);
+            self.position += len;
+            Ok(len)
+        }
+
+        fn seek(&mut self, position: u64) -> AudioResult<()> {
+            self.position = position as usize;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn banks_load_from_asset_sources() {
+        let backend = NullAudioBackend::new(&[]);
+        backend.add_unloaded_bank("Level.bank", &[EVENT]);
+        let source = TestAssetSource {
+            name: "Level.bank",
+            data: vec![0; 100],
+            size: 100,
+        };
+
+        backend
+            .load_bank_from_source("Level.bank", Arc::new(source))
+            .unwrap();
+        backend.get_event(EVENT).unwrap();
+    }
+
+    #[test]
+    fn asset_source_errors_fail_the_load() {
+        let backend = NullAudioBackend::new(&[]);
+        backend.add_unloaded_bank("Level.bank", &[EVENT]);
+        let missing = TestAssetSource {
+            name: "Other.bank",
+            data: Vec::new(),
+            size: 0,
+        };
+        assert!(
+            backend
+                .load_bank_from_source("Level.bank", Arc::new(missing))
+                .is_err()
+        );
+
+        let truncated = TestAssetSource {
+            name: "Level.bank",
+            data: vec![0; 10],
+            size: 100,
+        };
+        let error = backend
+            .load_bank_from_source("Level.bank", Arc::new(truncated))
+            .err()
+            .unwrap();
+        assert_eq!(error.code(), Some(FMOD_ERR_FILE_BAD));
+        assert!(backend.get_bank("Level.bank").is_err());
+    }
+
+    /// A game-controlled parameter from 0 to 10, defaulting to 1.
+    fn parameter(name: &str, data1: u32) -> AudioParameterDescription {
+        AudioParameterDescription {
+            name: name.to_owned(),
+            id: AudioParameterId { data1, data2: 0 },
+            minimum: 0.0,
+            maximum: 10.0,
+            default_value: 1.0,
+            kind: AudioParameterKind::GameControlled,
+            flags: AudioParameterFlags::default(),
+            labels: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn instance_parameters_start_at_their_defaults() {
+        let backend = NullAudioBackend::new(&[EVENT]);
+        backend.add_parameter(EVENT, parameter("Intensity", 1));
+        backend.add_parameter(EVENT, parameter("Speed", 2));
+        let inspector = backend.inspector();
+        let instance = backend.get_event(EVENT).unwrap().create_instance().unwrap();
+        assert_eq!(
+            instance.get_parameter_by_name("Speed").unwrap(),
+            AudioParameterValue {
+                value: 1.0,
+                final_value: 1.0
+            }
+        );
+
+        instance.set_parameter_by_name("Intensity", 5.0).unwrap();
+        instance
+            .set_parameter_by_id(AudioParameterId { data1: 2, data2: 0 }, 7.0)
+            .unwrap();
+        assert_eq!(
+            instance.get_parameter_by_name("Intensity").unwrap().value,
+            5.0
+        );
+        assert_eq!(instance.get_parameter_by_name("Speed").unwrap().value, 7.0);
+        assert_eq!(inspector.instances()[0].parameters["Speed"], 7.0);
+    }
+
+    #[test]
+    fn labeled_parameters_are_set_by_label() {
+        let backend = NullAudioBackend::new(&[EVENT]);
+        backend.add_parameter(
+            EVENT,
+            AudioParameterDescription {
+                labels: vec!["Calm".to_owned(), "Tense".to_owned()],
+                ..parameter("Mood", 1)
+            },
+        );
+        let inspector = backend.inspector();
+        let instance = backend.get_event(EVENT).unwrap().create_instance().unwrap();
+
+        instance
+            .set_parameter_by_name_with_label("Mood", "Tense")
+            .unwrap();
+        assert_eq!(instance.get_parameter_by_name("Mood").unwrap().value, 1.0);
+        assert_eq!(inspector.instances()[0].parameter_labels["Mood"], "Tense");
+        assert!(
+            instance
+                .set_parameter_by_name_with_label("Mood", "Angry")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn instance_parameters_must_be_settable() {
+        let backend = NullAudioBackend::new(&[EVENT]);
+        backend.add_parameter(
+            EVENT,
+            AudioParameterDescription {
+                flags: AudioParameterFlags {
+                    read_only: true,
+                    ..Default::default()
+                },
+                ..parameter("Read Only", 1)
+            },
+        );
+        backend.add_parameter(
+            EVENT,
+            AudioParameterDescription {
+                kind: AudioParameterKind::AutomaticDistance,
+                ..parameter("Distance", 2)
+            },
+        );
+        let instance = backend.get_event(EVENT).unwrap().create_instance().unwrap();
+
+        for name in ["Read Only", "Distance"] {
+            let error = instance.set_parameter_by_name(name, 2.0).unwrap_err();
+            assert_eq!(error.code(), Some(FMOD_ERR_INVALID_PARAM), "{name}");
+        }
+        assert_eq!(
+            instance
+                .set_parameter_by_name("Missing", 2.0)
+                .unwrap_err()
+                .code(),
+            Some(FMOD_ERR_EVENT_NOTFOUND)
+        );
+    }
+
+    #[test]
+    fn events_describe_their_parameters() {
+        let backend = NullAudioBackend::new(&[EVENT, "event:/Footstep"]);
+        let labeled = AudioParameterDescription {
+            labels: vec!["Calm".to_owned(), "Tense".to_owned()],
+            flags: AudioParameterFlags {
+                labeled: true,
+                discrete: true,
+                ..Default::default()
+            },
+            ..parameter("Mood", 1)
+        };
+        backend.add_parameter(EVENT, labeled.clone());
+        backend.add_parameter(EVENT, parameter("Speed", 2));
+
+        let parameters = backend.get_event(EVENT).unwrap().parameters().unwrap();
+        assert_eq!(parameters, vec![labeled, parameter("Speed", 2)]);
+        assert!(
+            backend
+                .get_event("event:/Footstep")
+                .unwrap()
+                .parameters()
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn global_parameters_are_set_through_handles() {
+        let backend = NullAudioBackend::new(&[]);
+        let global = AudioParameterDescription {
+            flags: AudioParameterFlags {
+                global: true,
+                ..Default::default()
+            },
+            ..parameter("Time Of Day", 3)
+        };
+        backend.add_global_parameter(global.clone());
+        let inspector = backend.inspector();
+        assert_eq!(backend.global_parameters().unwrap(), vec![global]);
+
+        let handle = backend.get_parameter_handle("Time Of Day").unwrap();
+        assert_eq!(handle.name(), "Time Of Day");
+        assert_eq!(handle.id(), AudioParameterId { data1: 3, data2: 0 });
+        backend.set_parameter(&handle, 6.0).unwrap();
+        assert_eq!(inspector.parameter("Time Of Day"), Some(6.0));
+        backend.set_parameter_by_name("Time Of Day", 7.0).unwrap();
+        assert_eq!(inspector.parameter("Time Of Day"), Some(7.0));
+    }
+
+    #[test]
+    fn undescribed_global_parameters_are_not_found() {
+        let backend = NullAudioBackend::new(&[]);
+        assert_eq!(
+            backend.get_parameter_handle("Missing").unwrap_err().code(),
+            Some(FMOD_ERR_EVENT_NOTFOUND)
+        );
+        assert!(backend.set_parameter_by_name("Missing", 1.0).is_err());
+        assert_eq!(backend.inspector().parameter("Missing"), None);
+    }
+
+    #[test]
+    fn callbacks_are_queued_once_enabled() {
+        let backend = NullAudioBackend::new(&[EVENT]);
+        let instance = backend.get_event(EVENT).unwrap().create_instance().unwrap();
+        assert!(instance.poll_callbacks().is_err());
+
+        // nothing is queued from before callbacks were enabled
+        backend.fire_callback(0, AudioEventCallback::SoundPlayed);
+        instance.enable_callbacks().unwrap();
+        instance.start().unwrap();
+        backend.update().unwrap();
+        let marker = AudioEventCallback::TimelineMarker {
+            name: "Drop".to_owned(),
+            position: 1000,
+        };
+        backend.fire_callback(0, marker.clone());
+        assert_eq!(
+            instance.poll_callbacks().unwrap(),
+            vec![
+                AudioEventCallback::Starting,
+                AudioEventCallback::Started,
+                marker
+            ]
+        );
+        assert!(instance.poll_callbacks().unwrap().is_empty());
+
+        instance.stop(AudioStopMode::AllowFadeout).unwrap();
+        assert!(instance.poll_callbacks().unwrap().is_empty());
+        backend.update().unwrap();
+        assert_eq!(
+            instance.poll_callbacks().unwrap(),
+            vec![AudioEventCallback::Stopped]
+        );
+    }
+
+    #[test]
+    fn bus_settings_are_kept() {
+        let backend = NullAudioBackend::new(&[]);
+        backend.add_bus("bus:/SFX");
+        let inspector = backend.inspector();
+        let bus = backend.get_bus("bus:/SFX").unwrap();
+
+        bus.set_volume(0.5).unwrap();
+        bus.set_mute(true).unwrap();
+        bus.set_paused(true).unwrap();
+        assert_eq!(
+            bus.get_volume().unwrap(),
+            AudioVolume {
+                volume: 0.5,
+                final_volume: 0.5
+            }
+        );
+        assert!(bus.get_mute().unwrap());
+        assert!(bus.get_paused().unwrap());
+        let record = &inspector.buses()[1];
+        assert_eq!(record.path, "bus:/SFX");
+        assert!(record.mute && record.paused);
+
+        assert!(matches!(
+            backend.get_bus("bus:/Missing"),
+            Err(AudioError::NotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn stopping_the_master_bus_stops_everything() {
+        let backend = NullAudioBackend::new(&[EVENT]);
+        backend.add_bus("bus:/SFX");
+        let inspector = backend.inspector();
+        let description = backend.get_event(EVENT).unwrap();
+        let first = description.create_instance().unwrap();
+        let second = description.create_instance().unwrap();
+        first.start().unwrap();
+        second.start().unwrap();
+        backend.update().unwrap();
+
+        // the null backend doesn't know what's routed into other buses
+        let sfx = backend.get_bus("bus:/SFX").unwrap();
+        sfx.stop_all_events(AudioStopMode::Immediate).unwrap();
+        assert_eq!(inspector.playing_count(EVENT), 2);
+        assert_eq!(
+            inspector.buses()[1].stop_all_events_calls,
+            vec![AudioStopMode::Immediate]
+        );
+
+        let master = backend.get_bus("bus:/").unwrap();
+        master.stop_all_events(AudioStopMode::Immediate).unwrap();
+        assert_eq!(inspector.playing_count(EVENT), 0);
+    }
+
+    #[test]
+    fn buses_are_only_metered_once_enabled() {
+        let backend = NullAudioBackend::new(&[]);
+        let bus = backend.get_bus("bus:/").unwrap();
+        assert_eq!(bus.get_metering().unwrap(), None);
+
+        bus.enable_metering().unwrap();
+        assert_eq!(
+            bus.get_metering().unwrap(),
+            Some(AudioMeteringInfo::default())
+        );
+    }
+
+    #[test]
+    fn vcas_come_and_go_with_their_banks() {
+        let backend = NullAudioBackend::new(&[]);
+        backend.add_vca("Master.bank", "vca:/Music");
+        backend.add_unloaded_bank("Level.bank", &[]);
+        backend.add_vca("Level.bank", "vca:/Ambience");
+        let inspector = backend.inspector();
+
+        let paths = |backend: &NullAudioBackend| -> Vec<String> {
+            backend
+                .vca_list()
+                .unwrap()
+                .iter()
+                .map(|v| v.get_path().unwrap())
+                .collect()
+        };
+        assert_eq!(paths(&backend), vec!["vca:/Music"]);
+        assert!(backend.get_vca("vca:/Ambience").is_err());
+
+        backend.load_bank_from_memory("Level.bank", &[]).unwrap();
+        assert_eq!(paths(&backend), vec!["vca:/Music", "vca:/Ambience"]);
+        let ambience = backend.get_vca("vca:/Ambience").unwrap();
+        assert_eq!(ambience.get_volume().unwrap().volume, 1.0);
+        ambience.set_volume(0.25).unwrap();
+        assert_eq!(ambience.get_volume().unwrap().volume, 0.25);
+        assert_eq!(inspector.vca_volume("vca:/Ambience"), Some(0.25));
+        assert_eq!(inspector.vca_volume("vca:/Music"), None);
+
+        backend.unload_bank("Level.bank").unwrap();
+        assert!(matches!(
+            ambience.set_volume(0.5),
+            Err(AudioError::InvalidHandle { .. })
+        ));
+        assert_eq!(paths(&backend), vec!["vca:/Music"]);
+    }
+
+    const SNAPSHOT: &str = "snapshot:/Underwater";
+
+    #[test]
+    fn snapshots_are_told_apart_from_events() {
+        let backend = NullAudioBackend::new(&[EVENT, SNAPSHOT]);
+        assert!(!backend.get_event(EVENT).unwrap().is_snapshot().unwrap());
+        assert!(backend.get_event(SNAPSHOT).unwrap().is_snapshot().unwrap());
+        assert!(matches!(
+            AudioSnapshot::new(backend.get_event(EVENT).unwrap().as_ref()),
+            Err(AudioError::InvalidParameter { .. })
+        ));
+    }
+
+    #[test]
+    fn snapshots_have_an_intensity() {
+        let backend = NullAudioBackend::new(&[SNAPSHOT]);
+        let snapshot = AudioSnapshot::new(backend.get_event(SNAPSHOT).unwrap().as_ref()).unwrap();
+        assert_eq!(snapshot.get_intensity().unwrap().value, 100.0);
+        snapshot.set_intensity(40.0).unwrap();
+        assert_eq!(snapshot.get_intensity().unwrap().value, 40.0);
+    }
+
+    #[test]
+    fn snapshot_stack_pops_most_recent_first() {
+        const PAUSED: &str = "snapshot:/Paused";
+        let backend = NullAudioBackend::new(&[SNAPSHOT, PAUSED]);
+        let inspector = backend.inspector();
+        let mut stack = AudioSnapshotStack::new();
+
+        stack.push(&backend, SNAPSHOT).unwrap();
+        stack.push(&backend, PAUSED).unwrap();
+        stack.push(&backend, SNAPSHOT).unwrap();
+        assert_eq!(stack.len(), 3);
+        assert_eq!(inspector.playing_count(SNAPSHOT), 2);
+
+        assert_eq!(stack.pop().unwrap().as_deref(), Some(SNAPSHOT));
+        // the other push of the same snapshot keeps it applied
+        assert!(stack.contains(SNAPSHOT));
+        assert_eq!(stack.top().unwrap().get_path(), PAUSED);
+        backend.update().unwrap();
+        assert_eq!(inspector.playing_count(SNAPSHOT), 1);
+
+        stack.clear().unwrap();
+        assert!(stack.is_empty());
+        assert_eq!(stack.pop().unwrap(), None);
+        backend.update().unwrap();
+        assert_eq!(inspector.playing_count(SNAPSHOT), 0);
+        assert_eq!(inspector.playing_count(PAUSED), 0);
+        assert!(inspector.instances().iter().all(|i| i.released));
+    }
+
+    #[test]
+    fn failed_bank_loads_report_full_progress() {
+        let backend = NullAudioBackend::new(&[]);
+        backend.add_unloaded_bank("Level.bank", &[EVENT]);
+        let loader = backend.load_bank("Level.bank").unwrap();
+        assert_eq!(loader.get_progress(), 0.0);
+
+        // nothing public fails a null load, so unload the bank out from under it
+        for bank in &mut backend.state.borrow_mut().banks {
+            if bank.filename == "Level.bank" {
+                bank.loading_state = AudioLoadingState::Unloaded;
+            }
+        }
+        assert!(matches!(loader.get_loaded(), Some(Err(_))));
+        assert_eq!(loader.get_progress(), 1.0);
+    }
+}
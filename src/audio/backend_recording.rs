@@ -0,0 +1,1129 @@
+//! A decorator backend which records every call made to the audio API into a JSON-lines trace, plus
+//! a replayer which can drive any backend from such a trace.
+//!
+//! This is intended for reproducing bug reports from players: wrap the real backend in a
+//! [RecordingAudioBackend] and have the player send the resulting trace, then feed it to an
+//! [AudioTraceReplayer] wrapping a local backend to hear (or inspect, via the null backend) exactly
+//! what the game did.
+//!
+//! Each line of a trace is one [AudioTraceEntry]. The frame counter advances each time
+//! [AudioBackend::update] is called, so replaying one frame per `update` reproduces the original
+//! timing. Event instances are identified by an id assigned in creation order. Pure queries (like
+//! [AudioEventInstance::get_playback_state]) don't affect what is heard, so they aren't recorded.
+//!
+//! Calls are recorded once they've been made, along with the error if they failed, so that a trace
+//! of a game hitting errors can still be replayed: calls which failed are expected to fail again.
+
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::io::{BufRead, Write};
+use std::rc::Rc;
+
+use super::*;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AudioTraceEntry {
+    pub frame: u64,
+    pub call: AudioCall,
+    /// Set if the call failed when it was recorded, in which case it's expected to fail again when
+    /// replayed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<AudioError>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AudioCall {
+    Update,
+    Shutdown,
+    GetEvent {
+        event_name: String,
+    },
+    SetListeners {
+        listeners: Vec<AudioListener>,
+    },
+    SetListeners3d {
+        listeners: Vec<AudioListener3d>,
+    },
+    SetListenerWeight {
+        index: usize,
+        weight: f32,
+    },
+    RemoveListener {
+        index: usize,
+    },
+    Set3dSettings {
+        doppler_scale: f32,
+        distance_factor: f32,
+        rolloff_scale: f32,
+    },
+    SetParameterByName {
+        name: String,
+        value: f32,
+    },
+    SetParameter {
+        handle: ParameterHandle,
+        value: f32,
+    },
+    LoadBank {
+        filename: String,
+    },
+    /// The bank data itself isn't recorded; it has to be given to the replayer via
+    /// [AudioTraceReplayer::add_bank_data].
+    LoadBankFromMemory {
+        name: String,
+        len: usize,
+    },
+    /// Like [AudioCall::LoadBankFromMemory], the source has to be given to the replayer via
+    /// [AudioTraceReplayer::add_bank_source].
+    LoadBankFromSource {
+        name: String,
+    },
+    LoadBankSampleData {
+        filename: String,
+    },
+    UnloadBankSampleData {
+        filename: String,
+    },
+    UnloadBank {
+        filename: String,
+    },
+    CreateInstance {
+        event_path: String,
+        instance_id: u64,
+    },
+    Release {
+        instance_id: u64,
+    },
+    Start {
+        instance_id: u64,
+    },
+    Stop {
+        instance_id: u64,
+        mode: AudioStopMode,
+    },
+    Set3dAttributes {
+        instance_id: u64,
+        position: Vec2,
+        velocity: Vec2,
+    },
+    SetAttributes3d {
+        instance_id: u64,
+        attributes: Audio3dAttributes,
+    },
+    SetInstanceParameterByName {
+        instance_id: u64,
+        name: String,
+        value: f32,
+    },
+    SetInstanceParameterById {
+        instance_id: u64,
+        id: AudioParameterId,
+        value: f32,
+    },
+    SetInstanceParameterByNameWithLabel {
+        instance_id: u64,
+        name: String,
+        label: String,
+    },
+    EnableCallbacks {
+        instance_id: u64,
+    },
+    SetBusVolume {
+        path: String,
+        volume: f32,
+    },
+    SetBusMute {
+        path: String,
+        mute: bool,
+    },
+    SetBusPaused {
+        path: String,
+        paused: bool,
+    },
+    StopAllBusEvents {
+        path: String,
+        mode: AudioStopMode,
+    },
+    SetVcaVolume {
+        path: String,
+        volume: f32,
+    },
+}
+
+struct TraceRecorder {
+    writer: Box<dyn Write>,
+    frame: u64,
+    next_instance_id: u64,
+}
+
+impl TraceRecorder {
+    fn record<T>(&mut self, call: AudioCall, result: &AudioResult<T>) -> AudioResult<()> {
+        let entry = AudioTraceEntry {
+            frame: self.frame,
+            call,
+            error: result.as_ref().err().cloned(),
+        };
+        serde_json::to_writer(&mut self.writer, &entry).context("Writing audio trace entry")?;
+        self.writer
+            .write_all(b"\n")
+            .context("Writing audio trace entry")?;
+        Ok(())
+    }
+}
+
+type SharedRecorder = Rc<RefCell<TraceRecorder>>;
+
+/// Record a call once it's been made, along with whether it failed, then pass its result on.
+fn record<T>(recorder: &SharedRecorder, call: AudioCall, result: AudioResult<T>) -> AudioResult<T> {
+    recorder.borrow_mut().record(call, &result)?;
+    result
+}
+
+pub struct RecordingAudioBackend {
+    inner: Box<dyn AudioBackend>,
+    recorder: SharedRecorder,
+}
+
+impl RecordingAudioBackend {
+    /// Wrap `inner` so that every call made to it is also written to `trace` as JSON lines.
+    pub fn new(inner: Box<dyn AudioBackend>, trace: impl Write + 'static) -> Self {
+        RecordingAudioBackend {
+            inner,
+            recorder: Rc::new(RefCell::new(TraceRecorder {
+                writer: Box::new(trace),
+                frame: 0,
+                next_instance_id: 0,
+            })),
+        }
+    }
+}
+
+fn wrap_bank(recorder: &SharedRecorder, inner: Box<dyn AudioBank>) -> Box<dyn AudioBank> {
+    Box::new(RecordingBank {
+        inner,
+        recorder: recorder.clone(),
+    })
+}
+
+fn wrap_description(
+    recorder: &SharedRecorder,
+    inner: Box<dyn AudioEventDescription>,
+    event_path: String,
+) -> Box<dyn AudioEventDescription> {
+    Box::new(RecordingEventDescription {
+        inner,
+        event_path,
+        recorder: recorder.clone(),
+    })
+}
+
+fn wrap_descriptions(
+    recorder: &SharedRecorder,
+    inner: Vec<Box<dyn AudioEventDescription>>,
+) -> AudioResult<Vec<Box<dyn AudioEventDescription>>> {
+    inner
+        .into_iter()
+        .map(|inner| {
+            let event_path = inner.get_path()?;
+            Ok(wrap_description(recorder, inner, event_path))
+        })
+        .collect()
+}
+
+fn wrap_vca(recorder: &SharedRecorder, inner: Box<dyn AudioVca>) -> AudioResult<Box<dyn AudioVca>> {
+    Ok(Box::new(RecordingVca {
+        path: inner.get_path()?,
+        inner,
+        recorder: recorder.clone(),
+    }))
+}
+
+impl AudioBackend for RecordingAudioBackend {
+    fn shutdown(self: Box<Self>) -> AudioResult<()> {
+        let result = self.inner.shutdown();
+        let mut recorder = self.recorder.borrow_mut();
+        recorder.record(AudioCall::Shutdown, &result)?;
+        recorder.writer.flush().context("Flushing audio trace")?;
+        result
+    }
+
+    fn update(&self) -> AudioResult<()> {
+        let result = self.inner.update();
+        let mut recorder = self.recorder.borrow_mut();
+        recorder.record(AudioCall::Update, &result)?;
+        // flush once per frame so that a crash loses at most the current frame
+        recorder.writer.flush().context("Flushing audio trace")?;
+        recorder.frame += 1;
+        result
+    }
+
+    fn get_event(&self, event_name: &str) -> AudioResult<Box<dyn AudioEventDescription>> {
+        let inner = record(
+            &self.recorder,
+            AudioCall::GetEvent {
+                event_name: event_name.to_owned(),
+            },
+            self.inner.get_event(event_name),
+        )?;
+        Ok(wrap_description(
+            &self.recorder,
+            inner,
+            event_name.to_owned(),
+        ))
+    }
+
+    fn get_event_list(&self) -> AudioResult<Vec<Box<dyn AudioEventDescription>>> {
+        wrap_descriptions(&self.recorder, self.inner.get_event_list()?)
+    }
+
+    fn get_bank(&self, filename: &str) -> AudioResult<Box<dyn AudioBank>> {
+        Ok(wrap_bank(&self.recorder, self.inner.get_bank(filename)?))
+    }
+
+    fn get_bank_list(&self) -> AudioResult<Vec<Box<dyn AudioBank>>> {
+        Ok(self
+            .inner
+            .get_bank_list()?
+            .into_iter()
+            .map(|b| wrap_bank(&self.recorder, b))
+            .collect())
+    }
+
+    fn load_bank(&self, filename: &str) -> AudioResult<Box<dyn AudioBankLoader>> {
+        let inner = record(
+            &self.recorder,
+            AudioCall::LoadBank {
+                filename: filename.to_owned(),
+            },
+            self.inner.load_bank(filename),
+        )?;
+        Ok(Box::new(RecordingBankLoader {
+            inner,
+            recorder: self.recorder.clone(),
+        }))
+    }
+
+    fn load_bank_from_memory(&self, name: &str, data: &[u8]) -> AudioResult<Box<dyn AudioBank>> {
+        let inner = record(
+            &self.recorder,
+            AudioCall::LoadBankFromMemory {
+                name: name.to_owned(),
+                len: data.len(),
+            },
+            self.inner.load_bank_from_memory(name, data),
+        )?;
+        Ok(wrap_bank(&self.recorder, inner))
+    }
+
+    fn load_bank_from_source(
+        &self,
+        name: &str,
+        source: Arc<dyn AudioAssetSource>,
+    ) -> AudioResult<Box<dyn AudioBank>> {
+        let inner = record(
+            &self.recorder,
+            AudioCall::LoadBankFromSource {
+                name: name.to_owned(),
+            },
+            self.inner.load_bank_from_source(name, source),
+        )?;
+        Ok(wrap_bank(&self.recorder, inner))
+    }
+
+    fn unload_bank(&self, filename: &str) -> AudioResult<()> {
+        record(
+            &self.recorder,
+            AudioCall::UnloadBank {
+                filename: filename.to_owned(),
+            },
+            self.inner.unload_bank(filename),
+        )
+    }
+
+    fn get_bus(&self, path: &str) -> AudioResult<Box<dyn AudioBus>> {
+        Ok(Box::new(RecordingBus {
+            inner: self.inner.get_bus(path)?,
+            path: path.to_owned(),
+            recorder: self.recorder.clone(),
+        }))
+    }
+
+    fn get_vca(&self, path: &str) -> AudioResult<Box<dyn AudioVca>> {
+        wrap_vca(&self.recorder, self.inner.get_vca(path)?)
+    }
+
+    fn vca_list(&self) -> AudioResult<Vec<Box<dyn AudioVca>>> {
+        self.inner
+            .vca_list()?
+            .into_iter()
+            .map(|vca| wrap_vca(&self.recorder, vca))
+            .collect()
+    }
+
+    fn set_listeners(&self, listeners: &[AudioListener]) -> AudioResult<()> {
+        record(
+            &self.recorder,
+            AudioCall::SetListeners {
+                listeners: listeners.to_vec(),
+            },
+            self.inner.set_listeners(listeners),
+        )
+    }
+
+    fn set_listeners_3d(&self, listeners: &[AudioListener3d]) -> AudioResult<()> {
+        record(
+            &self.recorder,
+            AudioCall::SetListeners3d {
+                listeners: listeners.to_vec(),
+            },
+            self.inner.set_listeners_3d(listeners),
+        )
+    }
+
+    fn set_listener_weight(&self, index: usize, weight: f32) -> AudioResult<()> {
+        record(
+            &self.recorder,
+            AudioCall::SetListenerWeight { index, weight },
+            self.inner.set_listener_weight(index, weight),
+        )
+    }
+
+    fn remove_listener(&self, index: usize) -> AudioResult<()> {
+        record(
+            &self.recorder,
+            AudioCall::RemoveListener { index },
+            self.inner.remove_listener(index),
+        )
+    }
+
+    fn set_3d_settings(
+        &self,
+        doppler_scale: f32,
+        distance_factor: f32,
+        rolloff_scale: f32,
+    ) -> AudioResult<()> {
+        record(
+            &self.recorder,
+            AudioCall::Set3dSettings {
+                doppler_scale,
+                distance_factor,
+                rolloff_scale,
+            },
+            self.inner
+                .set_3d_settings(doppler_scale, distance_factor, rolloff_scale),
+        )
+    }
+
+    fn set_parameter_by_name(&self, name: &str, value: f32) -> AudioResult<()> {
+        record(
+            &self.recorder,
+            AudioCall::SetParameterByName {
+                name: name.to_owned(),
+                value,
+            },
+            self.inner.set_parameter_by_name(name, value),
+        )
+    }
+
+    fn global_parameters(&self) -> AudioResult<Vec<AudioParameterDescription>> {
+        self.inner.global_parameters()
+    }
+
+    fn get_parameter_handle(&self, name: &str) -> AudioResult<ParameterHandle> {
+        self.inner.get_parameter_handle(name)
+    }
+
+    fn set_parameter(&self, handle: &ParameterHandle, value: f32) -> AudioResult<()> {
+        record(
+            &self.recorder,
+            AudioCall::SetParameter {
+                handle: handle.clone(),
+                value,
+            },
+            self.inner.set_parameter(handle, value),
+        )
+    }
+}
+
+struct RecordingBankLoader {
+    inner: Box<dyn AudioBankLoader>,
+    recorder: SharedRecorder,
+}
+
+impl AudioBankLoader for RecordingBankLoader {
+    fn get_progress(&self) -> f32 {
+        self.inner.get_progress()
+    }
+
+    fn get_loaded(&self) -> Option<AudioResult<Box<dyn AudioBank>>> {
+        self.inner
+            .get_loaded()
+            .map(|result| result.map(|inner| wrap_bank(&self.recorder, inner)))
+    }
+}
+
+struct RecordingBank {
+    inner: Box<dyn AudioBank>,
+    recorder: SharedRecorder,
+}
+
+impl AudioBank for RecordingBank {
+    fn get_filename(&self) -> String {
+        self.inner.get_filename()
+    }
+
+    fn get_path(&self) -> AudioResult<String> {
+        self.inner.get_path()
+    }
+
+    fn get_id(&self) -> AudioResult<String> {
+        self.inner.get_id()
+    }
+
+    fn get_loading_state(&self) -> AudioResult<AudioLoadingState> {
+        self.inner.get_loading_state()
+    }
+
+    fn get_sample_loading_state(&self) -> AudioResult<AudioLoadingState> {
+        self.inner.get_sample_loading_state()
+    }
+
+    fn load_sample_data(&self) -> AudioResult<()> {
+        record(
+            &self.recorder,
+            AudioCall::LoadBankSampleData {
+                filename: self.inner.get_filename(),
+            },
+            self.inner.load_sample_data(),
+        )
+    }
+
+    fn unload_sample_data(&self) -> AudioResult<()> {
+        record(
+            &self.recorder,
+            AudioCall::UnloadBankSampleData {
+                filename: self.inner.get_filename(),
+            },
+            self.inner.unload_sample_data(),
+        )
+    }
+
+    fn get_event_list(&self) -> AudioResult<Vec<Box<dyn AudioEventDescription>>> {
+        wrap_descriptions(&self.recorder, self.inner.get_event_list()?)
+    }
+
+    fn unload(self: Box<Self>) -> AudioResult<()> {
+        let filename = self.inner.get_filename();
+        record(
+            &self.recorder,
+            AudioCall::UnloadBank { filename },
+            self.inner.unload(),
+        )
+    }
+}
+
+struct RecordingEventDescription {
+    inner: Box<dyn AudioEventDescription>,
+    event_path: String,
+    recorder: SharedRecorder,
+}
+
+impl AudioEventDescription for RecordingEventDescription {
+    fn create_instance(&self) -> AudioResult<Box<dyn AudioEventInstance>> {
+        let result = self.inner.create_instance();
+        let instance_id = {
+            let mut recorder = self.recorder.borrow_mut();
+            let instance_id = recorder.next_instance_id;
+            recorder.record(
+                AudioCall::CreateInstance {
+                    event_path: self.event_path.clone(),
+                    instance_id,
+                },
+                &result,
+            )?;
+            // failed creations don't use up an id, since the replayer never gets an instance for
+            // them either
+            if result.is_ok() {
+                recorder.next_instance_id += 1;
+            }
+            instance_id
+        };
+
+        Ok(Box::new(RecordingEventInstance {
+            inner: result?,
+            instance_id,
+            recorder: self.recorder.clone(),
+        }))
+    }
+
+    fn get_path(&self) -> AudioResult<String> {
+        self.inner.get_path()
+    }
+
+    fn parameters(&self) -> AudioResult<Vec<AudioParameterDescription>> {
+        self.inner.parameters()
+    }
+
+    fn is_snapshot(&self) -> AudioResult<bool> {
+        self.inner.is_snapshot()
+    }
+}
+
+struct RecordingEventInstance {
+    inner: Box<dyn AudioEventInstance>,
+    instance_id: u64,
+    recorder: SharedRecorder,
+}
+
+impl RecordingEventInstance {
+    fn record<T>(&self, call: AudioCall, result: AudioResult<T>) -> AudioResult<T> {
+        record(&self.recorder, call, result)
+    }
+}
+
+impl AudioEventInstance for RecordingEventInstance {
+    fn release(self: Box<Self>) -> AudioResult<()> {
+        let call = AudioCall::Release {
+            instance_id: self.instance_id,
+        };
+        record(&self.recorder, call, self.inner.release())
+    }
+
+    fn start(&self) -> AudioResult<()> {
+        self.record(
+            AudioCall::Start {
+                instance_id: self.instance_id,
+            },
+            self.inner.start(),
+        )
+    }
+
+    fn stop(&self, mode: AudioStopMode) -> AudioResult<()> {
+        self.record(
+            AudioCall::Stop {
+                instance_id: self.instance_id,
+                mode,
+            },
+            self.inner.stop(mode),
+        )
+    }
+
+    fn set_3d_attributes(&self, position: Vec2, velocity: Vec2) -> AudioResult<()> {
+        self.record(
+            AudioCall::Set3dAttributes {
+                instance_id: self.instance_id,
+                position,
+                velocity,
+            },
+            self.inner.set_3d_attributes(position, velocity),
+        )
+    }
+
+    fn set_attributes_3d(&self, attributes: &Audio3dAttributes) -> AudioResult<()> {
+        self.record(
+            AudioCall::SetAttributes3d {
+                instance_id: self.instance_id,
+                attributes: *attributes,
+            },
+            self.inner.set_attributes_3d(attributes),
+        )
+    }
+
+    fn get_playback_state(&self) -> AudioResult<AudioPlaybackState> {
+        self.inner.get_playback_state()
+    }
+
+    fn set_parameter_by_name(&self, name: &str, value: f32) -> AudioResult<()> {
+        self.record(
+            AudioCall::SetInstanceParameterByName {
+                instance_id: self.instance_id,
+                name: name.to_owned(),
+                value,
+            },
+            self.inner.set_parameter_by_name(name, value),
+        )
+    }
+
+    fn set_parameter_by_id(&self, id: AudioParameterId, value: f32) -> AudioResult<()> {
+        self.record(
+            AudioCall::SetInstanceParameterById {
+                instance_id: self.instance_id,
+                id,
+                value,
+            },
+            self.inner.set_parameter_by_id(id, value),
+        )
+    }
+
+    fn set_parameter_by_name_with_label(&self, name: &str, label: &str) -> AudioResult<()> {
+        self.record(
+            AudioCall::SetInstanceParameterByNameWithLabel {
+                instance_id: self.instance_id,
+                name: name.to_owned(),
+                label: label.to_owned(),
+            },
+            self.inner.set_parameter_by_name_with_label(name, label),
+        )
+    }
+
+    fn get_parameter_by_name(&self, name: &str) -> AudioResult<AudioParameterValue> {
+        self.inner.get_parameter_by_name(name)
+    }
+
+    fn enable_callbacks(&self) -> AudioResult<()> {
+        self.record(
+            AudioCall::EnableCallbacks {
+                instance_id: self.instance_id,
+            },
+            self.inner.enable_callbacks(),
+        )
+    }
+
+    fn poll_callbacks(&self) -> AudioResult<Vec<AudioEventCallback>> {
+        self.inner.poll_callbacks()
+    }
+}
+
+/// Buses are identified in the trace by the path they were looked up with.
+struct RecordingBus {
+    inner: Box<dyn AudioBus>,
+    path: String,
+    recorder: SharedRecorder,
+}
+
+impl RecordingBus {
+    fn record<T>(&self, call: AudioCall, result: AudioResult<T>) -> AudioResult<T> {
+        record(&self.recorder, call, result)
+    }
+}
+
+impl AudioBus for RecordingBus {
+    fn get_path(&self) -> AudioResult<String> {
+        self.inner.get_path()
+    }
+
+    fn set_volume(&self, volume: f32) -> AudioResult<()> {
+        self.record(
+            AudioCall::SetBusVolume {
+                path: self.path.clone(),
+                volume,
+            },
+            self.inner.set_volume(volume),
+        )
+    }
+
+    fn get_volume(&self) -> AudioResult<AudioVolume> {
+        self.inner.get_volume()
+    }
+
+    fn set_mute(&self, mute: bool) -> AudioResult<()> {
+        self.record(
+            AudioCall::SetBusMute {
+                path: self.path.clone(),
+                mute,
+            },
+            self.inner.set_mute(mute),
+        )
+    }
+
+    fn get_mute(&self) -> AudioResult<bool> {
+        self.inner.get_mute()
+    }
+
+    fn set_paused(&self, paused: bool) -> AudioResult<()> {
+        self.record(
+            AudioCall::SetBusPaused {
+                path: self.path.clone(),
+                paused,
+            },
+            self.inner.set_paused(paused),
+        )
+    }
+
+    fn get_paused(&self) -> AudioResult<bool> {
+        self.inner.get_paused()
+    }
+
+    fn stop_all_events(&self, mode: AudioStopMode) -> AudioResult<()> {
+        self.record(
+            AudioCall::StopAllBusEvents {
+                path: self.path.clone(),
+                mode,
+            },
+            self.inner.stop_all_events(mode),
+        )
+    }
+
+    fn enable_metering(&self) -> AudioResult<()> {
+        self.inner.enable_metering()
+    }
+
+    fn get_metering(&self) -> AudioResult<Option<AudioMeteringInfo>> {
+        self.inner.get_metering()
+    }
+}
+
+/// Like buses, VCAs are identified in the trace by path.
+struct RecordingVca {
+    inner: Box<dyn AudioVca>,
+    path: String,
+    recorder: SharedRecorder,
+}
+
+impl AudioVca for RecordingVca {
+    fn get_path(&self) -> AudioResult<String> {
+        Ok(self.path.clone())
+    }
+
+    fn set_volume(&self, volume: f32) -> AudioResult<()> {
+        record(
+            &self.recorder,
+            AudioCall::SetVcaVolume {
+                path: self.path.clone(),
+                volume,
+            },
+            self.inner.set_volume(volume),
+        )
+    }
+
+    fn get_volume(&self) -> AudioResult<AudioVolume> {
+        self.inner.get_volume()
+    }
+}
+
+/// Drives a backend from a trace written by [RecordingAudioBackend].
+pub struct AudioTraceReplayer {
+    backend: Option<Box<dyn AudioBackend>>,
+    entries: VecDeque<AudioTraceEntry>,
+    frame: u64,
+    events: HashMap<String, Box<dyn AudioEventDescription>>,
+    instances: HashMap<u64, Box<dyn AudioEventInstance>>,
+    bank_data: HashMap<String, Vec<u8>>,
+    bank_sources: HashMap<String, Arc<dyn AudioAssetSource>>,
+}
+
+impl AudioTraceReplayer {
+    pub fn new(backend: Box<dyn AudioBackend>, trace: impl BufRead) -> AudioResult<Self> {
+        let mut entries = VecDeque::new();
+        for (line_idx, line) in trace.lines().enumerate() {
+            let line = line.context("Reading audio trace")?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry = serde_json::from_str(&line)
+                .with_context(|| format!("Parsing audio trace line {}", line_idx + 1))?;
+            entries.push_back(entry);
+        }
+
+        Ok(AudioTraceReplayer {
+            backend: Some(backend),
+            entries,
+            frame: 0,
+            events: HashMap::new(),
+            instances: HashMap::new(),
+            bank_data: HashMap::new(),
+            bank_sources: HashMap::new(),
+        })
+    }
+
+    /// Provide the data for a bank which the trace loads from memory.
+    pub fn add_bank_data(&mut self, name: &str, data: Vec<u8>) {
+        self.bank_data.insert(name.to_owned(), data);
+    }
+
+    /// Provide the source for a bank which the trace loads from an [AudioAssetSource].
+    pub fn add_bank_source(&mut self, name: &str, source: Arc<dyn AudioAssetSource>) {
+        self.bank_sources.insert(name.to_owned(), source);
+    }
+
+    /// Whether there are still calls in the trace which haven't been replayed.
+    pub fn is_finished(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Replay every call that was made during the next frame (including the `update` that ended
+    /// it, if there was one). Returns whether there is anything left to replay.
+    pub fn replay_frame(&mut self) -> AudioResult<bool> {
+        while self
+            .entries
+            .front()
+            .is_some_and(|entry| entry.frame <= self.frame)
+        {
+            let entry = self
+                .entries
+                .pop_front()
+                .expect("front entry was just checked");
+            match (self.replay_call(entry.call), entry.error) {
+                (Ok(()), None) | (Err(_), Some(_)) => {}
+                (Ok(()), Some(error)) => warn!(
+                    "Audio trace frame {}: call failed when recorded ({error}) but succeeded when \
+                     replayed",
+                    entry.frame
+                ),
+                (Err(e), None) => {
+                    return Err(e.context(format!("Replaying audio trace frame {}", entry.frame)));
+                }
+            }
+        }
+        self.frame += 1;
+
+        Ok(!self.is_finished())
+    }
+
+    /// Replay the whole trace as fast as possible.
+    pub fn replay_all(&mut self) -> AudioResult<()> {
+        while self.replay_frame()? {}
+        Ok(())
+    }
+
+    /// Take back the backend being driven, unless the trace has shut it down.
+    pub fn into_backend(self) -> Option<Box<dyn AudioBackend>> {
+        self.backend
+    }
+
+    fn backend(&self) -> AudioResult<&dyn AudioBackend> {
+        match &self.backend {
+            Some(backend) => Ok(backend.as_ref()),
+            None => Err(AudioError::other(
+                "audio trace uses the backend after shutting it down",
+            )),
+        }
+    }
+
+    fn event(&mut self, event_path: &str) -> AudioResult<&dyn AudioEventDescription> {
+        if !self.events.contains_key(event_path) {
+            let description = self.backend()?.get_event(event_path)?;
+            self.events.insert(event_path.to_owned(), description);
+        }
+        Ok(self.events[event_path].as_ref())
+    }
+
+    fn instance(&self, instance_id: u64) -> AudioResult<&dyn AudioEventInstance> {
+        match self.instances.get(&instance_id) {
+            Some(instance) => Ok(instance.as_ref()),
+            None => Err(AudioError::other(format!(
+                "audio trace refers to unknown event instance {instance_id}"
+            ))),
+        }
+    }
+
+    fn replay_call(&mut self, call: AudioCall) -> AudioResult<()> {
+        match call {
+            AudioCall::Update => self.backend()?.update(),
+            AudioCall::Shutdown => match self.backend.take() {
+                Some(backend) => {
+                    // handles become invalid once the system is released, so drop them first
+                    self.instances.clear();
+                    self.events.clear();
+                    backend.shutdown()
+                }
+                None => Err(AudioError::other(
+                    "audio trace shuts the backend down twice",
+                )),
+            },
+            AudioCall::GetEvent { event_name } => self.event(&event_name).map(|_| ()),
+            AudioCall::SetListeners { listeners } => self.backend()?.set_listeners(&listeners),
+            AudioCall::SetListeners3d { listeners } => self.backend()?.set_listeners_3d(&listeners),
+            AudioCall::SetListenerWeight { index, weight } => {
+                self.backend()?.set_listener_weight(index, weight)
+            }
+            AudioCall::RemoveListener { index } => self.backend()?.remove_listener(index),
+            AudioCall::Set3dSettings {
+                doppler_scale,
+                distance_factor,
+                rolloff_scale,
+            } => self
+                .backend()?
+                .set_3d_settings(doppler_scale, distance_factor, rolloff_scale),
+            AudioCall::SetParameterByName { name, value } => {
+                self.backend()?.set_parameter_by_name(&name, value)
+            }
+            AudioCall::SetParameter { handle, value } => {
+                self.backend()?.set_parameter(&handle, value)
+            }
+            AudioCall::LoadBank { filename } => {
+                // the bank keeps loading in the background even once we drop the loader
+                self.backend()?.load_bank(&filename).map(|_| ())
+            }
+            AudioCall::LoadBankFromMemory { name, len } => {
+                let Some(data) = self.bank_data.get(&name) else {
+                    return Err(AudioError::other(format!(
+                        "audio trace loads bank {name} from memory but its data wasn't provided"
+                    )));
+                };
+                if data.len() != len {
+                    warn!(
+                        "Bank data for {name} is {} bytes but the traced bank was {len} bytes",
+                        data.len()
+                    );
+                }
+                self.backend()?
+                    .load_bank_from_memory(&name, data)
+                    .map(|_| ())
+            }
+            AudioCall::LoadBankFromSource { name } => {
+                let Some(source) = self.bank_sources.get(&name) else {
+                    return Err(AudioError::other(format!(
+                        "audio trace loads bank {name} from a source but it wasn't provided"
+                    )));
+                };
+                self.backend()?
+                    .load_bank_from_source(&name, source.clone())
+                    .map(|_| ())
+            }
+            AudioCall::LoadBankSampleData { filename } => {
+                self.backend()?.get_bank(&filename)?.load_sample_data()
+            }
+            AudioCall::UnloadBankSampleData { filename } => {
+                self.backend()?.get_bank(&filename)?.unload_sample_data()
+            }
+            AudioCall::UnloadBank { filename } => {
+                // descriptions from the unloaded bank are now invalid, so look them up again later
+                self.events.clear();
+                self.backend()?.unload_bank(&filename)
+            }
+            AudioCall::CreateInstance {
+                event_path,
+                instance_id,
+            } => {
+                let instance = self.event(&event_path)?.create_instance()?;
+                self.instances.insert(instance_id, instance);
+                Ok(())
+            }
+            AudioCall::Release { instance_id } => match self.instances.remove(&instance_id) {
+                Some(instance) => instance.release(),
+                None => Err(AudioError::other(format!(
+                    "audio trace releases unknown event instance {instance_id}"
+                ))),
+            },
+            AudioCall::Start { instance_id } => self.instance(instance_id)?.start(),
+            AudioCall::Stop { instance_id, mode } => self.instance(instance_id)?.stop(mode),
+            AudioCall::Set3dAttributes {
+                instance_id,
+                position,
+                velocity,
+            } => self
+                .instance(instance_id)?
+                .set_3d_attributes(position, velocity),
+            AudioCall::SetAttributes3d {
+                instance_id,
+                attributes,
+            } => self.instance(instance_id)?.set_attributes_3d(&attributes),
+            AudioCall::SetInstanceParameterByName {
+                instance_id,
+                name,
+                value,
+            } => self
+                .instance(instance_id)?
+                .set_parameter_by_name(&name, value),
+            AudioCall::SetInstanceParameterById {
+                instance_id,
+                id,
+                value,
+            } => self.instance(instance_id)?.set_parameter_by_id(id, value),
+            AudioCall::SetInstanceParameterByNameWithLabel {
+                instance_id,
+                name,
+                label,
+            } => self
+                .instance(instance_id)?
+                .set_parameter_by_name_with_label(&name, &label),
+            AudioCall::EnableCallbacks { instance_id } => {
+                self.instance(instance_id)?.enable_callbacks()
+            }
+            AudioCall::SetBusVolume { path, volume } => {
+                self.backend()?.get_bus(&path)?.set_volume(volume)
+            }
+            AudioCall::SetBusMute { path, mute } => self.backend()?.get_bus(&path)?.set_mute(mute),
+            AudioCall::SetBusPaused { path, paused } => {
+                self.backend()?.get_bus(&path)?.set_paused(paused)
+            }
+            AudioCall::StopAllBusEvents { path, mode } => {
+                self.backend()?.get_bus(&path)?.stop_all_events(mode)
+            }
+            AudioCall::SetVcaVolume { path, volume } => {
+                self.backend()?.get_vca(&path)?.set_volume(volume)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::backend_null::NullAudioBackend;
+    use super::*;
+
+    const EVENT: &str = "event:/Music/Level 01";
+
+    /// A trace which can still be read once the recording backend has taken it.
+    #[derive(Clone, Default)]
+    struct SharedTrace(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedTrace {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn replay_reproduces_recorded_calls() {
+        let trace = SharedTrace::default();
+        let original = NullAudioBackend::new(&[EVENT]);
+        let original_inspector = original.inspector();
+        let backend = Box::new(RecordingAudioBackend::new(
+            Box::new(original),
+            trace.clone(),
+        ));
+
+        backend
+            .set_listeners(&[AudioListener {
+                weight: 1.0,
+                position: Vec2::new(1.0, 2.0),
+                velocity: Vec2::ZERO,
+                attenuation_position: None,
+            }])
+            .unwrap();
+        let instance = backend.get_event(EVENT).unwrap().create_instance().unwrap();
+        instance
+            .set_3d_attributes(Vec2::new(3.0, 4.0), Vec2::ZERO)
+            .unwrap();
+        instance.start().unwrap();
+        backend.update().unwrap();
+        // failed calls shouldn't stop the trace from being replayed
+        assert!(backend.get_event("event:/Missing").is_err());
+        assert!(instance.set_parameter_by_name("Missing", 1.0).is_err());
+        instance.stop(AudioStopMode::AllowFadeout).unwrap();
+        backend.update().unwrap();
+        backend.shutdown().unwrap();
+
+        let trace = trace.0.borrow();
+        let failed = trace
+            .split(|&b| b == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(|line| serde_json::from_slice::<AudioTraceEntry>(line).unwrap())
+            .filter(|entry| entry.error.is_some())
+            .count();
+        assert_eq!(failed, 2);
+
+        let replayed = NullAudioBackend::new(&[EVENT]);
+        let replayed_inspector = replayed.inspector();
+        let mut replayer = AudioTraceReplayer::new(Box::new(replayed), trace.as_slice()).unwrap();
+        replayer.replay_all().unwrap();
+
+        assert_eq!(
+            replayed_inspector.instances(),
+            original_inspector.instances()
+        );
+        assert_eq!(
+            replayed_inspector.listeners(),
+            original_inspector.listeners()
+        );
+        assert_eq!(replayed_inspector.update_count(), 2);
+        assert!(replayed_inspector.is_shut_down());
+    }
+}
@@ -1,5 +1,8 @@
+use std::collections::HashMap;
+
 use wasm_bindgen::prelude::*;
 
+use super::error::*;
 use super::*;
 
 type FmodResult<T> = Result<T, JsValue>;
@@ -10,7 +13,47 @@ trait FmodResultExt<T> {
 
 impl<T> FmodResultExt<T> for FmodResult<T> {
     fn to_result(self) -> AudioResult<T> {
-        self.map_err(|e| anyhow::anyhow!("fmod-web.js error: {:?}", e))
+        self.map_err(AudioError::from)
+    }
+}
+
+/// The objects fmod-web.js throws: `code` is the `FMOD_RESULT` for errors from FMOD, and `kind` is
+/// the [AudioError] variant for errors fmod-web.js raises itself. Anything else thrown (e.g. a
+/// JavaScript `TypeError`) becomes [AudioError::Other].
+#[derive(Deserialize)]
+struct FmodWebError {
+    code: Option<FmodResultCode>,
+    kind: Option<String>,
+    message: String,
+}
+
+impl From<JsValue> for AudioError {
+    fn from(value: JsValue) -> Self {
+        let Ok(error) = serde_wasm_bindgen::from_value::<FmodWebError>(value.clone()) else {
+            return AudioError::other(match value.as_string() {
+                Some(message) => format!("fmod-web.js error: {message}"),
+                None => format!("fmod-web.js error: {value:?}"),
+            });
+        };
+        let message = error.message;
+        match (error.code, error.kind.as_deref()) {
+            (Some(code), _) => AudioError::from_fmod_code(code, message),
+            (None, Some("BankNotFound")) => AudioError::BankNotFound {
+                message,
+                code: None,
+            },
+            (None, Some("InvalidParameter")) => AudioError::InvalidParameter {
+                message,
+                code: None,
+            },
+            (None, _) => AudioError::other(message),
+        }
+    }
+}
+
+impl From<serde_wasm_bindgen::Error> for AudioError {
+    fn from(e: serde_wasm_bindgen::Error) -> Self {
+        AudioError::other(e.to_string())
     }
 }
 
@@ -22,8 +65,21 @@ extern "C" {
 
     type FmodLoader;
 
+    /// "Pending", "Ready" or "Failed".
+    #[wasm_bindgen(method)]
+    fn get_state(this: &FmodLoader) -> String;
+
+    /// An array of objects matching [BankDownloadProgress].
+    #[wasm_bindgen(method)]
+    fn get_download_progress(this: &FmodLoader) -> JsValue;
+
+    /// The error which stopped initialization, or null.
+    #[wasm_bindgen(method)]
+    fn get_error(this: &FmodLoader) -> JsValue;
+
+    /// Get the backend, or null if it's still loading.
     #[wasm_bindgen(method, catch)]
-    fn get_loaded(this: &FmodLoader) -> FmodResult<FmodWebBackend>;
+    fn get_loaded(this: &FmodLoader) -> FmodResult<Option<FmodWebBackend>>;
 
     type FmodWebBackend;
 
@@ -43,14 +99,140 @@ extern "C" {
     #[wasm_bindgen(method, catch)]
     fn get_event_list(this: &FmodWebBackend) -> FmodResult<Vec<FmodEventDescription>>;
 
-    /// Set the listeners for the fmod system.
+    /// Get a loaded bank by the filename it was loaded from.
+    #[wasm_bindgen(method, catch)]
+    fn get_bank(this: &FmodWebBackend, filename: &str) -> FmodResult<FmodBank>;
+
+    /// Get a list of all loaded banks.
+    #[wasm_bindgen(method, catch)]
+    fn get_bank_list(this: &FmodWebBackend) -> FmodResult<Vec<FmodBank>>;
+
+    /// Start loading a bank, fetching it over HTTP first if necessary.
+    #[wasm_bindgen(method, catch)]
+    fn load_bank(this: &FmodWebBackend, filename: &str) -> FmodResult<FmodBankLoader>;
+
+    /// Load a bank from a buffer in memory, synchronously.
+    #[wasm_bindgen(method, catch)]
+    fn load_bank_from_memory(
+        this: &FmodWebBackend,
+        name: &str,
+        data: &[u8],
+    ) -> FmodResult<FmodBank>;
+
+    /// Load a bank whose data is read via file callbacks which call into `source`.
     #[wasm_bindgen(method, catch)]
-    fn set_listeners(this: &FmodWebBackend, listeners: Vec<JsValue>) -> FmodResult<()>;
+    fn load_bank_from_source(
+        this: &FmodWebBackend,
+        name: &str,
+        source: WebAssetSource,
+    ) -> FmodResult<FmodBank>;
+
+    /// Unload a bank by the filename it was loaded from.
+    #[wasm_bindgen(method, catch)]
+    fn unload_bank(this: &FmodWebBackend, filename: &str) -> FmodResult<()>;
+
+    /// Get a mixer bus by path.
+    #[wasm_bindgen(method, catch)]
+    fn get_bus(this: &FmodWebBackend, path: &str) -> FmodResult<FmodBus>;
+
+    /// Get a VCA by path.
+    #[wasm_bindgen(method, catch)]
+    fn get_vca(this: &FmodWebBackend, path: &str) -> FmodResult<FmodVca>;
+
+    /// Get all VCAs in the loaded banks.
+    #[wasm_bindgen(method, catch)]
+    fn get_vca_list(this: &FmodWebBackend) -> FmodResult<Vec<FmodVca>>;
+
+    /// Set the listeners for the fmod system, as [AudioListener3d]s in FMOD's coordinates.
+    #[wasm_bindgen(method, catch)]
+    fn set_listeners_3d(this: &FmodWebBackend, listeners: Vec<JsValue>) -> FmodResult<()>;
+
+    /// Set the weight of one of the listeners last set.
+    #[wasm_bindgen(method, catch)]
+    fn set_listener_weight(this: &FmodWebBackend, index: usize, weight: f32) -> FmodResult<()>;
+
+    /// Remove one of the listeners last set, moving later ones down.
+    #[wasm_bindgen(method, catch)]
+    fn remove_listener(this: &FmodWebBackend, index: usize) -> FmodResult<()>;
+
+    /// Set the core system's doppler scale, distance factor and rolloff scale.
+    #[wasm_bindgen(method, catch)]
+    fn set_3d_settings(
+        this: &FmodWebBackend,
+        doppler_scale: f32,
+        distance_factor: f32,
+        rolloff_scale: f32,
+    ) -> FmodResult<()>;
 
     /// Set a global parameter by name for the fmod system.
     #[wasm_bindgen(method, catch)]
     fn set_parameter_by_name(this: &FmodWebBackend, name: &str, value: f32) -> FmodResult<()>;
 
+    /// Get descriptions of all global parameters.
+    #[wasm_bindgen(method, catch)]
+    fn get_global_parameters(this: &FmodWebBackend) -> FmodResult<Vec<JsValue>>;
+
+    /// Look up the ID of a global parameter by name.
+    #[wasm_bindgen(method, catch)]
+    fn get_parameter_id(this: &FmodWebBackend, name: &str) -> FmodResult<JsValue>;
+
+    /// Set a global parameter by ID for the fmod system.
+    #[wasm_bindgen(method, catch)]
+    fn set_parameter_by_id(
+        this: &FmodWebBackend,
+        data1: u32,
+        data2: u32,
+        value: f32,
+    ) -> FmodResult<()>;
+
+    type FmodBankLoader;
+
+    /// Get how far along loading the bank is, from 0 to 1.
+    #[wasm_bindgen(method)]
+    fn get_progress(this: &FmodBankLoader) -> f32;
+
+    /// Get the bank, or null if it's still loading.
+    #[wasm_bindgen(method, catch)]
+    fn get_loaded(this: &FmodBankLoader) -> FmodResult<Option<FmodBank>>;
+
+    type FmodBank;
+
+    /// Get the filename the bank was loaded from.
+    #[wasm_bindgen(method)]
+    fn get_filename(this: &FmodBank) -> String;
+
+    /// Get the path of a bank.
+    #[wasm_bindgen(method, catch)]
+    fn get_path(this: &FmodBank) -> FmodResult<String>;
+
+    /// Get the GUID of a bank, as a string.
+    #[wasm_bindgen(method, catch)]
+    fn get_id(this: &FmodBank) -> FmodResult<String>;
+
+    /// Get the loading state of a bank.
+    #[wasm_bindgen(method, catch)]
+    fn get_loading_state(this: &FmodBank) -> FmodResult<JsValue>;
+
+    /// Get the loading state of a bank's sample data.
+    #[wasm_bindgen(method, catch)]
+    fn get_sample_loading_state(this: &FmodBank) -> FmodResult<JsValue>;
+
+    /// Load the sample data for all events in a bank.
+    #[wasm_bindgen(method, catch)]
+    fn load_sample_data(this: &FmodBank) -> FmodResult<()>;
+
+    /// Unload the sample data for all events in a bank.
+    #[wasm_bindgen(method, catch)]
+    fn unload_sample_data(this: &FmodBank) -> FmodResult<()>;
+
+    /// Get a list of all events in a bank.
+    #[wasm_bindgen(method, catch)]
+    fn get_event_list(this: &FmodBank) -> FmodResult<Vec<FmodEventDescription>>;
+
+    /// Unload a bank.
+    #[wasm_bindgen(method, catch)]
+    fn unload(this: &FmodBank) -> FmodResult<()>;
+
     type FmodEventDescription;
 
     /// Create an instance of an event.
@@ -65,6 +247,21 @@ extern "C" {
     #[wasm_bindgen(method, catch)]
     fn load_sample_data(this: &FmodEventDescription) -> FmodResult<()>;
 
+    /// Get whether an event is a snapshot.
+    #[wasm_bindgen(method, catch)]
+    fn is_snapshot(this: &FmodEventDescription) -> FmodResult<bool>;
+
+    /// Get the number of parameters used by an event.
+    #[wasm_bindgen(method, catch)]
+    fn get_parameter_description_count(this: &FmodEventDescription) -> FmodResult<u32>;
+
+    /// Get the description of a parameter, including its labels.
+    #[wasm_bindgen(method, catch)]
+    fn get_parameter_description_by_index(
+        this: &FmodEventDescription,
+        index: u32,
+    ) -> FmodResult<JsValue>;
+
     type FmodEventInstance;
 
     /// Release an event instance
@@ -77,129 +274,761 @@ extern "C" {
 
     /// Stop playing an event.
     #[wasm_bindgen(method, catch)]
-    fn stop(this: &FmodEventInstance) -> FmodResult<()>;
+    fn stop(this: &FmodEventInstance, mode: JsValue) -> FmodResult<()>;
 
-    /// Set the 3d attributes of an event instance.
+    /// Set the 3d attributes of an event instance, in FMOD's coordinates.
     #[wasm_bindgen(method, catch)]
-    fn set_3d_attributes(
-        this: &FmodEventInstance,
-        position: JsValue,
-        velocity: JsValue,
-    ) -> FmodResult<()>;
+    fn set_attributes_3d(this: &FmodEventInstance, attributes: JsValue) -> FmodResult<()>;
 
     /// Get the playback state of an event instance.
     #[wasm_bindgen(method, catch)]
     fn get_playback_state(this: &FmodEventInstance) -> FmodResult<JsValue>;
+
+    /// Set a parameter of an event instance by name.
+    #[wasm_bindgen(method, catch)]
+    fn set_parameter_by_name(this: &FmodEventInstance, name: &str, value: f32) -> FmodResult<()>;
+
+    /// Set a parameter of an event instance by ID.
+    #[wasm_bindgen(method, catch)]
+    fn set_parameter_by_id(
+        this: &FmodEventInstance,
+        data1: u32,
+        data2: u32,
+        value: f32,
+    ) -> FmodResult<()>;
+
+    /// Set a labeled parameter of an event instance by name.
+    #[wasm_bindgen(method, catch)]
+    fn set_parameter_by_name_with_label(
+        this: &FmodEventInstance,
+        name: &str,
+        label: &str,
+    ) -> FmodResult<()>;
+
+    /// Get the value and final value of a parameter of an event instance.
+    #[wasm_bindgen(method, catch)]
+    fn get_parameter_by_name(this: &FmodEventInstance, name: &str) -> FmodResult<JsValue>;
+
+    /// Start queueing up callbacks for an event instance.
+    #[wasm_bindgen(method, catch)]
+    fn enable_callbacks(this: &FmodEventInstance) -> FmodResult<()>;
+
+    /// Take the callbacks queued up since the last poll.
+    #[wasm_bindgen(method, catch)]
+    fn poll_callbacks(this: &FmodEventInstance) -> FmodResult<Vec<JsValue>>;
+
+    type FmodBus;
+
+    /// Get the path of a bus.
+    #[wasm_bindgen(method, catch)]
+    fn get_path(this: &FmodBus) -> FmodResult<String>;
+
+    /// Set the volume of a bus.
+    #[wasm_bindgen(method, catch)]
+    fn set_volume(this: &FmodBus, volume: f32) -> FmodResult<()>;
+
+    /// Get the volume and final volume of a bus.
+    #[wasm_bindgen(method, catch)]
+    fn get_volume(this: &FmodBus) -> FmodResult<JsValue>;
+
+    /// Mute or unmute a bus.
+    #[wasm_bindgen(method, catch)]
+    fn set_mute(this: &FmodBus, mute: bool) -> FmodResult<()>;
+
+    /// Get whether a bus is muted.
+    #[wasm_bindgen(method, catch)]
+    fn get_mute(this: &FmodBus) -> FmodResult<bool>;
+
+    /// Pause or unpause a bus.
+    #[wasm_bindgen(method, catch)]
+    fn set_paused(this: &FmodBus, paused: bool) -> FmodResult<()>;
+
+    /// Get whether a bus is paused.
+    #[wasm_bindgen(method, catch)]
+    fn get_paused(this: &FmodBus) -> FmodResult<bool>;
+
+    /// Stop all events routed into a bus.
+    #[wasm_bindgen(method, catch)]
+    fn stop_all_events(this: &FmodBus, mode: JsValue) -> FmodResult<()>;
+
+    /// Lock the channel group of a bus so that it can be metered.
+    #[wasm_bindgen(method, catch)]
+    fn enable_metering(this: &FmodBus) -> FmodResult<()>;
+
+    /// Get the output levels of a bus, or null if there aren't any yet (as for
+    /// [AudioBus::get_metering]).
+    #[wasm_bindgen(method, catch)]
+    fn get_metering(this: &FmodBus) -> FmodResult<JsValue>;
+
+    type FmodVca;
+
+    /// Get the path of a VCA.
+    #[wasm_bindgen(method, catch)]
+    fn get_path(this: &FmodVca) -> FmodResult<String>;
+
+    /// Set the volume of a VCA.
+    #[wasm_bindgen(method, catch)]
+    fn set_volume(this: &FmodVca, volume: f32) -> FmodResult<()>;
+
+    /// Get the volume and final volume of a VCA.
+    #[wasm_bindgen(method, catch)]
+    fn get_volume(this: &FmodVca) -> FmodResult<JsValue>;
+}
+
+/// Exposes an [AudioAssetSource] to fmod-web.js, so that its FMOD file callbacks can read from it.
+/// Open streams are referred to by integer handles since that's what FMOD passes around.
+#[wasm_bindgen]
+pub struct WebAssetSource {
+    name: String,
+    source: Arc<dyn AudioAssetSource>,
+    streams: HashMap<u32, Box<dyn AudioAssetStream>>,
+    next_handle: u32,
+}
+
+#[wasm_bindgen]
+impl WebAssetSource {
+    /// Open a new stream over the asset, returning its handle.
+    pub fn open(&mut self) -> FmodResult<u32> {
+        let stream = self
+            .source
+            .open(&self.name)
+            .map_err(|e| JsValue::from_str(&format!("Opening audio asset {}: {e:?}", self.name)))?;
+        // handle 0 would look like a null handle to FMOD
+        self.next_handle += 1;
+        self.streams.insert(self.next_handle, stream);
+        Ok(self.next_handle)
+    }
+
+    pub fn size(&self, handle: u32) -> FmodResult<u32> {
+        let size = self.stream(handle)?.size();
+        u32::try_from(size).map_err(|_| JsValue::from_str("audio asset is too large for FMOD"))
+    }
+
+    /// Read up to `len` bytes from the stream; fewer bytes are returned at the end of the asset.
+    pub fn read(&mut self, handle: u32, len: u32) -> FmodResult<Vec<u8>> {
+        let stream = self.stream_mut(handle)?;
+        let mut buffer = vec![0; len as usize];
+        let mut total = 0;
+        while total < buffer.len() {
+            let read = stream
+                .read(&mut buffer[total..])
+                .map_err(|e| JsValue::from_str(&format!("Reading audio asset: {e:?}")))?;
+            if read == 0 {
+                break;
+            }
+            total += read;
+        }
+        buffer.truncate(total);
+        Ok(buffer)
+    }
+
+    pub fn seek(&mut self, handle: u32, position: u32) -> FmodResult<()> {
+        self.stream_mut(handle)?
+            .seek(position as u64)
+            .map_err(|e| JsValue::from_str(&format!("Seeking audio asset: {e:?}")))
+    }
+
+    pub fn close(&mut self, handle: u32) {
+        self.streams.remove(&handle);
+    }
+}
+
+impl WebAssetSource {
+    fn stream(&self, handle: u32) -> FmodResult<&dyn AudioAssetStream> {
+        match self.streams.get(&handle) {
+            Some(stream) => Ok(stream.as_ref()),
+            None => Err(JsValue::from_str("unknown audio asset handle")),
+        }
+    }
+
+    fn stream_mut(&mut self, handle: u32) -> FmodResult<&mut Box<dyn AudioAssetStream>> {
+        self.streams
+            .get_mut(&handle)
+            .ok_or_else(|| JsValue::from_str("unknown audio asset handle"))
+    }
+}
+
+pub fn load_audio_backend(
+    base_path: &str,
+    banks: &[&str],
+    space: AudioSpaceConfig,
+) -> Box<dyn AudioBackendLoader> {
+    let loader = space.validate().map(|()| {
+        load_fmod(
+            base_path,
+            banks.into_iter().map(|s| s.to_string()).collect(),
+        )
+    });
+    if let Err(e) = &loader {
+        error!("Error initializing FMOD: {e}");
+    }
+    Box::new(WebAudioBackendLoader { loader, space })
 }
 
-pub fn load_audio_backend(base_path: &str, banks: &[&str]) -> Box<dyn AudioBackendLoader> {
-    Box::new(load_fmod(
-        base_path,
-        banks.into_iter().map(|s| s.to_string()).collect(),
-    ))
+/// fmod-web.js only ever sees FMOD's coordinates, so that [AudioSpaceConfig] is the one place
+/// they're converted; the wrappers below carry the backend's space to wherever that's needed.
+struct WebAudioBackendLoader {
+    /// FMOD isn't loaded at all if the space config is invalid.
+    loader: AudioResult<FmodLoader>,
+    space: AudioSpaceConfig,
 }
 
-impl AudioBackendLoader for FmodLoader {
+impl AudioBackendLoader for WebAudioBackendLoader {
+    fn get_state(&self) -> LoadState {
+        let loader = match &self.loader {
+            Ok(loader) => loader,
+            Err(e) => return LoadState::Failed(e.clone()),
+        };
+        match loader.get_state().as_str() {
+            "Pending" => LoadState::Pending(AudioBackendLoader::get_progress(self).fraction()),
+            "Ready" => LoadState::Ready,
+            "Failed" => {
+                LoadState::Failed(AudioError::from(loader.get_error()).context("Initializing FMOD"))
+            }
+            state => LoadState::Failed(AudioError::other(format!(
+                "Unknown FMOD loading state: {state}"
+            ))),
+        }
+    }
+
+    fn get_progress(&self) -> LoadProgress {
+        let Ok(loader) = &self.loader else {
+            return LoadProgress { banks: Vec::new() };
+        };
+        LoadProgress {
+            banks: serde_wasm_bindgen::from_value(loader.get_download_progress())
+                .expect("download progress deserialization should succeed"),
+        }
+    }
+
     fn get_loaded(&self) -> Option<AudioResult<Box<dyn AudioBackend>>> {
-        match FmodLoader::get_loaded(self) {
-            Ok(backend) => {
+        let loader = match &self.loader {
+            Ok(loader) => loader,
+            Err(e) => return Some(Err(e.clone())),
+        };
+        match loader.get_loaded() {
+            Ok(Some(backend)) => {
                 info!("FMOD backend loaded");
-                Some(Ok(Box::new(backend)))
-            }
-            Err(e) => {
-                //TODO audio: check for whether there was an error, or whether we're still waiting
-                trace!("FMOD load state: {:?}", e);
-                // try again later
-                None
+                Some(Ok(Box::new(WebAudioBackend {
+                    backend,
+                    space: self.space,
+                })))
             }
+            Ok(None) => None,
+            Err(e) => Some(Err(AudioError::from(e).context("Initializing FMOD"))),
         }
     }
 }
 
-impl AudioBackend for FmodWebBackend {
+struct WebAudioBackend {
+    backend: FmodWebBackend,
+    space: AudioSpaceConfig,
+}
+
+impl WebAudioBackend {
+    fn wrap_bank(&self, bank: FmodBank) -> Box<dyn AudioBank> {
+        Box::new(WebBank {
+            bank,
+            space: self.space,
+        })
+    }
+
+    fn wrap_description(
+        &self,
+        description: FmodEventDescription,
+    ) -> Box<dyn AudioEventDescription> {
+        Box::new(WebEventDescription {
+            description,
+            space: self.space,
+        })
+    }
+}
+
+impl AudioBackend for WebAudioBackend {
     fn update(&self) -> AudioResult<()> {
-        FmodWebBackend::update(&self).to_result()
+        self.backend.update().to_result()
     }
     fn shutdown(self: Box<Self>) -> AudioResult<()> {
-        FmodWebBackend::shutdown(&self).to_result()
+        self.backend.shutdown().to_result()
     }
 
     fn get_event(&self, event_name: &str) -> AudioResult<Box<dyn AudioEventDescription>> {
-        FmodWebBackend::get_event(&self, event_name)
-            .map(|r| Box::new(r) as Box<dyn AudioEventDescription>)
-            .to_result()
+        self.backend
+            .get_event(event_name)
+            .map(|d| self.wrap_description(d))
+            .map_err(|e| AudioError::from(e).for_event(event_name))
     }
 
     fn get_event_list(&self) -> AudioResult<Vec<Box<dyn AudioEventDescription>>> {
-        FmodWebBackend::get_event_list(&self)
-            .map(|r| {
-                r.into_iter()
-                    .map(|e| Box::new(e) as Box<dyn AudioEventDescription>)
+        self.backend
+            .get_event_list()
+            .map(|r| r.into_iter().map(|d| self.wrap_description(d)).collect())
+            .to_result()
+    }
+
+    fn get_bank(&self, filename: &str) -> AudioResult<Box<dyn AudioBank>> {
+        self.backend
+            .get_bank(filename)
+            .map(|b| self.wrap_bank(b))
+            .to_result()
+    }
+
+    fn get_bank_list(&self) -> AudioResult<Vec<Box<dyn AudioBank>>> {
+        self.backend
+            .get_bank_list()
+            .map(|r| r.into_iter().map(|b| self.wrap_bank(b)).collect())
+            .to_result()
+    }
+
+    fn load_bank(&self, filename: &str) -> AudioResult<Box<dyn AudioBankLoader>> {
+        self.backend
+            .load_bank(filename)
+            .map(|loader| {
+                Box::new(WebBankLoader {
+                    loader,
+                    space: self.space,
+                }) as Box<dyn AudioBankLoader>
+            })
+            .to_result()
+    }
+
+    fn load_bank_from_memory(&self, name: &str, data: &[u8]) -> AudioResult<Box<dyn AudioBank>> {
+        self.backend
+            .load_bank_from_memory(name, data)
+            .map(|b| self.wrap_bank(b))
+            .to_result()
+    }
+
+    fn load_bank_from_source(
+        &self,
+        name: &str,
+        source: Arc<dyn AudioAssetSource>,
+    ) -> AudioResult<Box<dyn AudioBank>> {
+        let source = WebAssetSource {
+            name: name.to_owned(),
+            source,
+            streams: HashMap::new(),
+            next_handle: 0,
+        };
+        self.backend
+            .load_bank_from_source(name, source)
+            .map(|b| self.wrap_bank(b))
+            .to_result()
+    }
+
+    fn unload_bank(&self, filename: &str) -> AudioResult<()> {
+        self.backend.unload_bank(filename).to_result()
+    }
+
+    fn get_bus(&self, path: &str) -> AudioResult<Box<dyn AudioBus>> {
+        self.backend
+            .get_bus(path)
+            .map(|r| Box::new(r) as Box<dyn AudioBus>)
+            .to_result()
+    }
+
+    fn get_vca(&self, path: &str) -> AudioResult<Box<dyn AudioVca>> {
+        self.backend
+            .get_vca(path)
+            .map(|r| Box::new(r) as Box<dyn AudioVca>)
+            .to_result()
+    }
+
+    fn vca_list(&self) -> AudioResult<Vec<Box<dyn AudioVca>>> {
+        self.backend
+            .get_vca_list()
+            .map(|vs| {
+                vs.into_iter()
+                    .map(|v| Box::new(v) as Box<dyn AudioVca>)
                     .collect()
             })
             .to_result()
     }
 
     fn set_listeners(&self, listeners: &[AudioListener]) -> AudioResult<()> {
-        FmodWebBackend::set_listeners(
-            &self,
-            listeners
-                .iter()
-                .map(|l| {
-                    serde_wasm_bindgen::to_value(&l).expect("listener serialization should succeed")
-                })
-                .collect(),
-        )
-        .to_result()
+        let listeners: Vec<_> = listeners.iter().map(|l| l.to_3d(&self.space)).collect();
+        self.set_listeners_3d(&listeners)
+    }
+
+    fn set_listeners_3d(&self, listeners: &[AudioListener3d]) -> AudioResult<()> {
+        if listeners.is_empty() {
+            return Err(AudioError::InvalidParameter {
+                message: "At least one listener is needed".to_owned(),
+                code: None,
+            });
+        }
+        self.backend
+            .set_listeners_3d(
+                listeners
+                    .iter()
+                    .map(|l| {
+                        let listener = AudioListener3d {
+                            weight: l.weight,
+                            attributes: self.space.fmod_attributes(&l.attributes),
+                            attenuation_position: l
+                                .attenuation_position
+                                .map(|p| self.space.fmod_position(p)),
+                        };
+                        serde_wasm_bindgen::to_value(&listener)
+                            .expect("listener serialization should succeed")
+                    })
+                    .collect(),
+            )
+            .to_result()
+    }
+
+    fn set_listener_weight(&self, index: usize, weight: f32) -> AudioResult<()> {
+        self.backend.set_listener_weight(index, weight).to_result()
+    }
+
+    fn remove_listener(&self, index: usize) -> AudioResult<()> {
+        self.backend.remove_listener(index).to_result()
+    }
+
+    fn set_3d_settings(
+        &self,
+        doppler_scale: f32,
+        distance_factor: f32,
+        rolloff_scale: f32,
+    ) -> AudioResult<()> {
+        self.backend
+            .set_3d_settings(doppler_scale, distance_factor, rolloff_scale)
+            .to_result()
     }
 
     fn set_parameter_by_name(&self, name: &str, value: f32) -> AudioResult<()> {
-        FmodWebBackend::set_parameter_by_name(&self, name, value).to_result()
+        self.backend.set_parameter_by_name(name, value).to_result()
+    }
+
+    fn global_parameters(&self) -> AudioResult<Vec<AudioParameterDescription>> {
+        self.backend
+            .get_global_parameters()
+            .map(|ds| {
+                ds.into_iter()
+                    .map(|d| {
+                        serde_wasm_bindgen::from_value(d)
+                            .expect("parameter description deserialization should succeed")
+                    })
+                    .collect()
+            })
+            .to_result()
+    }
+
+    fn get_parameter_handle(&self, name: &str) -> AudioResult<ParameterHandle> {
+        self.backend
+            .get_parameter_id(name)
+            .map(|id| ParameterHandle {
+                name: name.to_owned(),
+                id: serde_wasm_bindgen::from_value(id)
+                    .expect("parameter id deserialization should succeed"),
+            })
+            .to_result()
+    }
+
+    fn set_parameter(&self, handle: &ParameterHandle, value: f32) -> AudioResult<()> {
+        let id = handle.id();
+        self.backend
+            .set_parameter_by_id(id.data1, id.data2, value)
+            .to_result()
+    }
+}
+
+struct WebBankLoader {
+    loader: FmodBankLoader,
+    space: AudioSpaceConfig,
+}
+
+impl AudioBankLoader for WebBankLoader {
+    fn get_progress(&self) -> f32 {
+        self.loader.get_progress()
+    }
+
+    fn get_loaded(&self) -> Option<AudioResult<Box<dyn AudioBank>>> {
+        self.loader.get_loaded().to_result().transpose().map(|r| {
+            r.map(|bank| {
+                Box::new(WebBank {
+                    bank,
+                    space: self.space,
+                }) as Box<dyn AudioBank>
+            })
+        })
+    }
+}
+
+fn deserialize_loading_state(state: JsValue) -> AudioLoadingState {
+    serde_wasm_bindgen::from_value(state)
+        .expect("bank loading state deserialization should succeed")
+}
+
+struct WebBank {
+    bank: FmodBank,
+    /// Passed on to the bank's events.
+    space: AudioSpaceConfig,
+}
+
+impl AudioBank for WebBank {
+    fn get_filename(&self) -> String {
+        self.bank.get_filename()
+    }
+
+    fn get_path(&self) -> AudioResult<String> {
+        self.bank.get_path().to_result()
+    }
+
+    fn get_id(&self) -> AudioResult<String> {
+        self.bank.get_id().to_result()
+    }
+
+    fn get_loading_state(&self) -> AudioResult<AudioLoadingState> {
+        self.bank
+            .get_loading_state()
+            .map(deserialize_loading_state)
+            .to_result()
+    }
+
+    fn get_sample_loading_state(&self) -> AudioResult<AudioLoadingState> {
+        self.bank
+            .get_sample_loading_state()
+            .map(deserialize_loading_state)
+            .to_result()
+    }
+
+    fn load_sample_data(&self) -> AudioResult<()> {
+        self.bank.load_sample_data().to_result()
+    }
+
+    fn unload_sample_data(&self) -> AudioResult<()> {
+        self.bank.unload_sample_data().to_result()
+    }
+
+    fn get_event_list(&self) -> AudioResult<Vec<Box<dyn AudioEventDescription>>> {
+        self.bank
+            .get_event_list()
+            .map(|r| {
+                r.into_iter()
+                    .map(|description| {
+                        Box::new(WebEventDescription {
+                            description,
+                            space: self.space,
+                        }) as Box<dyn AudioEventDescription>
+                    })
+                    .collect()
+            })
+            .to_result()
+    }
+
+    fn unload(self: Box<Self>) -> AudioResult<()> {
+        self.bank.unload().to_result()
     }
 }
 
-impl AudioEventDescription for FmodEventDescription {
+struct WebEventDescription {
+    description: FmodEventDescription,
+    space: AudioSpaceConfig,
+}
+
+impl AudioEventDescription for WebEventDescription {
     fn create_instance(&self) -> AudioResult<Box<dyn AudioEventInstance>> {
-        FmodEventDescription::create_instance(&self)
-            .map(|r| Box::new(r) as Box<dyn AudioEventInstance>)
+        self.description
+            .create_instance()
+            .map(|instance| {
+                Box::new(WebEventInstance {
+                    instance,
+                    space: self.space,
+                }) as Box<dyn AudioEventInstance>
+            })
             .to_result()
     }
 
     fn get_path(&self) -> AudioResult<String> {
-        FmodEventDescription::get_path(&self).to_result()
+        self.description.get_path().to_result()
+    }
+
+    fn parameters(&self) -> AudioResult<Vec<AudioParameterDescription>> {
+        let count = self
+            .description
+            .get_parameter_description_count()
+            .to_result()?;
+        (0..count)
+            .map(|index| {
+                self.description
+                    .get_parameter_description_by_index(index)
+                    .map(|d| {
+                        serde_wasm_bindgen::from_value(d)
+                            .expect("parameter description deserialization should succeed")
+                    })
+                    .to_result()
+            })
+            .collect()
+    }
+
+    fn is_snapshot(&self) -> AudioResult<bool> {
+        self.description.is_snapshot().to_result()
     }
 }
 
-impl AudioEventInstance for FmodEventInstance {
+struct WebEventInstance {
+    instance: FmodEventInstance,
+    space: AudioSpaceConfig,
+}
+
+impl AudioEventInstance for WebEventInstance {
     fn release(self: Box<Self>) -> AudioResult<()> {
-        FmodEventInstance::release(&self).to_result()
+        self.instance.release().to_result()
     }
 
     fn start(&self) -> AudioResult<()> {
-        FmodEventInstance::start(&self).to_result()
+        self.instance.start().to_result()
     }
 
-    fn stop(&self) -> AudioResult<()> {
-        FmodEventInstance::stop(&self).to_result()
+    fn stop(&self, mode: AudioStopMode) -> AudioResult<()> {
+        self.instance
+            .stop(
+                serde_wasm_bindgen::to_value(&mode)
+                    .expect("stop mode serialization should succeed"),
+            )
+            .to_result()
     }
 
     fn set_3d_attributes(&self, position: Vec2, velocity: Vec2) -> AudioResult<()> {
-        FmodEventInstance::set_3d_attributes(
-            &self,
-            serde_wasm_bindgen::to_value(&position)
-                .expect("instance 3d attr position serialization should succeed"),
-            serde_wasm_bindgen::to_value(&velocity)
-                .expect("instance 3d attr velocity serialization should succeed"),
-        )
-        .to_result()
+        self.set_attributes_3d(&self.space.attributes_from_2d(position, velocity))
+    }
+
+    fn set_attributes_3d(&self, attributes: &Audio3dAttributes) -> AudioResult<()> {
+        self.instance
+            .set_attributes_3d(
+                serde_wasm_bindgen::to_value(&self.space.fmod_attributes(attributes))
+                    .expect("instance 3d attributes serialization should succeed"),
+            )
+            .to_result()
     }
 
     fn get_playback_state(&self) -> AudioResult<AudioPlaybackState> {
-        FmodEventInstance::get_playback_state(&self)
+        self.instance
+            .get_playback_state()
             .map(|s| {
                 serde_wasm_bindgen::from_value(s)
                     .expect("event instance playback state deserialization should succeed")
             })
             .to_result()
     }
+
+    fn set_parameter_by_name(&self, name: &str, value: f32) -> AudioResult<()> {
+        self.instance.set_parameter_by_name(name, value).to_result()
+    }
+
+    fn set_parameter_by_id(&self, id: AudioParameterId, value: f32) -> AudioResult<()> {
+        self.instance
+            .set_parameter_by_id(id.data1, id.data2, value)
+            .to_result()
+    }
+
+    fn set_parameter_by_name_with_label(&self, name: &str, label: &str) -> AudioResult<()> {
+        self.instance
+            .set_parameter_by_name_with_label(name, label)
+            .to_result()
+    }
+
+    fn get_parameter_by_name(&self, name: &str) -> AudioResult<AudioParameterValue> {
+        self.instance
+            .get_parameter_by_name(name)
+            .map(|v| {
+                serde_wasm_bindgen::from_value(v)
+                    .expect("parameter value deserialization should succeed")
+            })
+            .to_result()
+    }
+
+    fn enable_callbacks(&self) -> AudioResult<()> {
+        self.instance.enable_callbacks().to_result()
+    }
+
+    fn poll_callbacks(&self) -> AudioResult<Vec<AudioEventCallback>> {
+        self.instance
+            .poll_callbacks()
+            .map(|cs| {
+                cs.into_iter()
+                    .map(|c| {
+                        serde_wasm_bindgen::from_value(c)
+                            .expect("event callback deserialization should succeed")
+                    })
+                    .collect()
+            })
+            .to_result()
+    }
+}
+
+impl AudioBus for FmodBus {
+    fn get_path(&self) -> AudioResult<String> {
+        FmodBus::get_path(self).to_result()
+    }
+
+    fn set_volume(&self, volume: f32) -> AudioResult<()> {
+        FmodBus::set_volume(self, volume).to_result()
+    }
+
+    fn get_volume(&self) -> AudioResult<AudioVolume> {
+        FmodBus::get_volume(self)
+            .map(|v| {
+                serde_wasm_bindgen::from_value(v)
+                    .expect("bus volume deserialization should succeed")
+            })
+            .to_result()
+    }
+
+    fn set_mute(&self, mute: bool) -> AudioResult<()> {
+        FmodBus::set_mute(self, mute).to_result()
+    }
+
+    fn get_mute(&self) -> AudioResult<bool> {
+        FmodBus::get_mute(self).to_result()
+    }
+
+    fn set_paused(&self, paused: bool) -> AudioResult<()> {
+        FmodBus::set_paused(self, paused).to_result()
+    }
+
+    fn get_paused(&self) -> AudioResult<bool> {
+        FmodBus::get_paused(self).to_result()
+    }
+
+    fn stop_all_events(&self, mode: AudioStopMode) -> AudioResult<()> {
+        FmodBus::stop_all_events(
+            self,
+            serde_wasm_bindgen::to_value(&mode).expect("stop mode serialization should succeed"),
+        )
+        .to_result()
+    }
+
+    fn enable_metering(&self) -> AudioResult<()> {
+        FmodBus::enable_metering(self).to_result()
+    }
+
+    fn get_metering(&self) -> AudioResult<Option<AudioMeteringInfo>> {
+        FmodBus::get_metering(self)
+            .map(|m| {
+                serde_wasm_bindgen::from_value(m)
+                    .expect("bus metering info deserialization should succeed")
+            })
+            .to_result()
+    }
+}
+
+impl AudioVca for FmodVca {
+    fn get_path(&self) -> AudioResult<String> {
+        FmodVca::get_path(self).to_result()
+    }
+
+    fn set_volume(&self, volume: f32) -> AudioResult<()> {
+        FmodVca::set_volume(self, volume).to_result()
+    }
+
+    fn get_volume(&self) -> AudioResult<AudioVolume> {
+        FmodVca::get_volume(self)
+            .map(|v| {
+                serde_wasm_bindgen::from_value(v)
+                    .expect("VCA volume deserialization should succeed")
+            })
+            .to_result()
+    }
 }
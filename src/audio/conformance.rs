@@ -0,0 +1,661 @@
+//! Behaviour every [AudioBackend] should share, so that the game behaves the same whichever backend
+//! it ends up running on.
+//!
+//! The checks only go through the traits, so each backend's tests point them at a backend set up to
+//! match a [ConformanceFixture] - the desktop backend runs them with FMOD's `NOSOUND` output
+//! against the banks from FMOD's Studio examples, and the null backend against a mirror of those.
+//! Anything that's asynchronous on FMOD (bank loading, sample loading, playback state changes) is
+//! checked by updating until it happens, so backends which do it all on the next update pass too.
+
+use std::io::Cursor;
+use std::io::{Read, Seek, SeekFrom};
+
+use super::*;
+
+/// How many updates to wait for something asynchronous to happen before giving up.
+const MAX_UPDATES: u32 = 500;
+
+/// An event which isn't in any bank.
+const MISSING_EVENT: &str = "event:/Does Not Exist";
+
+/// What the backend under test is expected to contain.
+pub struct ConformanceFixture {
+    /// Banks loaded when the backend was created, including the master and strings banks.
+    pub loaded_banks: Vec<&'static str>,
+    /// One of `loaded_banks`, containing `looping_event`.
+    pub looping_event_bank: &'static str,
+    /// An event which plays until it's stopped.
+    pub looping_event: &'static str,
+    /// An event with a game controlled parameter called `parameter`.
+    pub parameter_event: &'static str,
+    pub parameter: &'static str,
+    /// A bank which isn't loaded to start with, along with its data.
+    pub unloaded_bank: &'static str,
+    pub unloaded_bank_data: Vec<u8>,
+    /// An event in `unloaded_bank`.
+    pub unloaded_bank_event: &'static str,
+    /// A global parameter, if the banks have one.
+    pub global_parameter: Option<&'static str>,
+    /// A VCA in one of the loaded banks, if they have one.
+    pub vca: Option<&'static str>,
+}
+
+impl ConformanceFixture {
+    /// The banks that come with FMOD's Studio examples, in `api/studio/examples/media/`.
+    /// `vehicles_bank_data` is the contents of `Vehicles.bank`.
+    pub fn fmod_examples(vehicles_bank_data: Vec<u8>) -> Self {
+        ConformanceFixture {
+            loaded_banks: vec!["Master.bank", "Master.strings.bank", "SFX.bank"],
+            looping_event_bank: "SFX.bank",
+            looping_event: "event:/Ambience/Country",
+            parameter_event: "event:/Character/Player Footsteps",
+            parameter: "Surface",
+            unloaded_bank: "Vehicles.bank",
+            unloaded_bank_data: vehicles_bank_data,
+            unloaded_bank_event: "event:/Vehicles/Ride-on Mower",
+            global_parameter: None,
+            vca: None,
+        }
+    }
+}
+
+/// Run every check against `backend`, then shut it down.
+pub fn check_backend(
+    backend: Box<dyn AudioBackend>,
+    fixture: &ConformanceFixture,
+) -> AudioResult<()> {
+    check_events(&*backend, fixture).context("events")?;
+    check_banks(&*backend, fixture).context("banks")?;
+    check_sample_data(&*backend, fixture).context("sample data")?;
+    check_bank_loading(&*backend, fixture).context("bank loading")?;
+    check_playback(&*backend, fixture).context("playback")?;
+    check_stop_modes(&*backend, fixture.looping_event).context("stop modes")?;
+    check_event_parameters(&*backend, fixture).context("event parameters")?;
+    check_global_parameters(&*backend, fixture).context("global parameters")?;
+    check_master_bus(&*backend, fixture).context("master bus")?;
+    check_vcas(&*backend, fixture).context("VCAs")?;
+    check_listeners(&*backend).context("listeners")?;
+    backend.shutdown().context("shutdown")
+}
+
+/// Update until `done` returns true. FMOD Studio processes commands on its own thread, so it gets a
+/// little real time between updates.
+fn update_until(
+    backend: &dyn AudioBackend,
+    what: &str,
+    mut done: impl FnMut() -> AudioResult<bool>,
+) -> AudioResult<()> {
+    for _ in 0..MAX_UPDATES {
+        backend.update()?;
+        if done()? {
+            return Ok(());
+        }
+        std::thread::sleep(std::time::Duration::from_millis(2));
+    }
+    Err(AudioError::other(format!("gave up waiting for {what}")))
+}
+
+/// Load a bank with [AudioBackend::load_bank] and wait for it. Errors can come from either
+/// `load_bank` or the loader, depending on the backend.
+fn load_bank_and_wait(
+    backend: &dyn AudioBackend,
+    filename: &str,
+) -> AudioResult<Box<dyn AudioBank>> {
+    let loader = backend.load_bank(filename)?;
+    for _ in 0..MAX_UPDATES {
+        backend.update()?;
+        if let Some(result) = loader.get_loaded() {
+            assert_eq!(
+                loader.get_progress(),
+                1.0,
+                "finished loads should report full progress, whether or not they failed"
+            );
+            return result;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(2));
+    }
+    Err(AudioError::other(format!(
+        "gave up waiting for {filename} to load"
+    )))
+}
+
+fn event_paths(events: Vec<Box<dyn AudioEventDescription>>) -> AudioResult<Vec<String>> {
+    events.iter().map(|e| e.get_path()).collect()
+}
+
+/// Only the callbacks FMOD fires for every event, regardless of its content.
+fn lifecycle_callbacks(callbacks: Vec<AudioEventCallback>) -> Vec<AudioEventCallback> {
+    callbacks
+        .into_iter()
+        .filter(|c| {
+            matches!(
+                c,
+                AudioEventCallback::Starting
+                    | AudioEventCallback::Started
+                    | AudioEventCallback::Restarted
+                    | AudioEventCallback::Stopped
+                    | AudioEventCallback::StartFailed
+            )
+        })
+        .collect()
+}
+
+/// Poll an instance's callbacks until `last` comes in. Callbacks can arrive on FMOD's own thread
+/// slightly after the playback state has changed.
+fn poll_lifecycle_callbacks_until(
+    backend: &dyn AudioBackend,
+    instance: &dyn AudioEventInstance,
+    last: AudioEventCallback,
+) -> AudioResult<Vec<AudioEventCallback>> {
+    let mut callbacks = lifecycle_callbacks(instance.poll_callbacks()?);
+    if callbacks.last() != Some(&last) {
+        update_until(backend, "callbacks", || {
+            callbacks.extend(lifecycle_callbacks(instance.poll_callbacks()?));
+            Ok(callbacks.last() == Some(&last))
+        })?;
+    }
+    Ok(callbacks)
+}
+
+fn check_events(backend: &dyn AudioBackend, fixture: &ConformanceFixture) -> AudioResult<()> {
+    let description = backend.get_event(fixture.looping_event)?;
+    assert_eq!(description.get_path()?, fixture.looping_event);
+    assert!(!description.is_snapshot()?);
+
+    assert!(matches!(
+        backend.get_event(MISSING_EVENT),
+        Err(AudioError::EventNotFound { .. })
+    ));
+    assert!(
+        backend.get_event(fixture.unloaded_bank_event).is_err(),
+        "events in unloaded banks shouldn't be found"
+    );
+
+    let paths = event_paths(backend.get_event_list()?)?;
+    assert!(paths.iter().any(|p| p == fixture.looping_event));
+    assert!(paths.iter().any(|p| p == fixture.parameter_event));
+    assert!(!paths.iter().any(|p| p == fixture.unloaded_bank_event));
+    Ok(())
+}
+
+fn check_banks(backend: &dyn AudioBackend, fixture: &ConformanceFixture) -> AudioResult<()> {
+    let mut filenames: Vec<_> = backend
+        .get_bank_list()?
+        .iter()
+        .map(|b| b.get_filename())
+        .collect();
+    filenames.sort();
+    let mut expected = fixture.loaded_banks.clone();
+    expected.sort();
+    assert_eq!(filenames, expected);
+
+    for filename in &fixture.loaded_banks {
+        let bank = backend.get_bank(filename)?;
+        assert_eq!(bank.get_filename(), *filename);
+        assert_eq!(
+            bank.get_path()?,
+            format!("bank:/{}", filename.trim_end_matches(".bank"))
+        );
+        let id = bank.get_id()?;
+        assert!(
+            id.len() == 38 && id.starts_with('{') && id.ends_with('}'),
+            "bank IDs should be formatted GUIDs, got {id}"
+        );
+        assert_eq!(bank.get_loading_state()?, AudioLoadingState::Loaded);
+    }
+
+    let bank = backend.get_bank(fixture.looping_event_bank)?;
+    let paths = event_paths(bank.get_event_list()?)?;
+    assert!(paths.iter().any(|p| p == fixture.looping_event));
+
+    assert!(backend.get_bank(fixture.unloaded_bank).is_err());
+    assert!(matches!(
+        backend.get_bank("Does Not Exist.bank"),
+        Err(AudioError::BankNotFound { .. })
+    ));
+    Ok(())
+}
+
+fn check_sample_data(backend: &dyn AudioBackend, fixture: &ConformanceFixture) -> AudioResult<()> {
+    // every backend preloads the master bank's samples
+    let master = backend.get_bank("Master.bank")?;
+    update_until(backend, "master bank samples to load", || {
+        Ok(master.get_sample_loading_state()? == AudioLoadingState::Loaded)
+    })?;
+
+    let bank = backend.get_bank(fixture.looping_event_bank)?;
+    assert_eq!(
+        bank.get_sample_loading_state()?,
+        AudioLoadingState::Unloaded
+    );
+    bank.load_sample_data()?;
+    update_until(backend, "bank samples to load", || {
+        Ok(bank.get_sample_loading_state()? == AudioLoadingState::Loaded)
+    })?;
+    bank.unload_sample_data()?;
+    update_until(backend, "bank samples to unload", || {
+        Ok(bank.get_sample_loading_state()? == AudioLoadingState::Unloaded)
+    })?;
+    Ok(())
+}
+
+fn check_bank_loading(backend: &dyn AudioBackend, fixture: &ConformanceFixture) -> AudioResult<()> {
+    assert!(matches!(
+        load_bank_and_wait(backend, "Does Not Exist.bank"),
+        Err(AudioError::BankNotFound { .. })
+    ));
+    assert!(matches!(
+        backend.unload_bank("Does Not Exist.bank"),
+        Err(AudioError::BankNotFound { .. })
+    ));
+
+    let bank = load_bank_and_wait(backend, fixture.unloaded_bank)?;
+    assert_eq!(bank.get_filename(), fixture.unloaded_bank);
+    assert_eq!(bank.get_loading_state()?, AudioLoadingState::Loaded);
+    assert!(
+        backend
+            .get_bank_list()?
+            .iter()
+            .any(|b| b.get_filename() == fixture.unloaded_bank)
+    );
+    let description = backend.get_event(fixture.unloaded_bank_event)?;
+
+    backend.unload_bank(fixture.unloaded_bank)?;
+    assert!(backend.get_bank(fixture.unloaded_bank).is_err());
+    update_until(backend, "bank handle to be invalidated", || {
+        Ok(bank.get_loading_state().is_err())
+    })?;
+    assert!(backend.get_event(fixture.unloaded_bank_event).is_err());
+    assert!(
+        description.create_instance().is_err(),
+        "descriptions from unloaded banks should be invalidated"
+    );
+
+    let bank = backend.load_bank_from_memory(fixture.unloaded_bank, &fixture.unloaded_bank_data)?;
+    assert_eq!(bank.get_filename(), fixture.unloaded_bank);
+    backend.get_event(fixture.unloaded_bank_event)?;
+    bank.unload()?;
+    update_until(backend, "memory bank to unload", || {
+        Ok(backend.get_event(fixture.unloaded_bank_event).is_err())
+    })?;
+
+    let source = Arc::new(MemoryAssetSource {
+        name: fixture.unloaded_bank.to_owned(),
+        data: fixture.unloaded_bank_data.clone().into(),
+    });
+    let bank = backend.load_bank_from_source(fixture.unloaded_bank, source)?;
+    assert_eq!(bank.get_filename(), fixture.unloaded_bank);
+    backend.get_event(fixture.unloaded_bank_event)?;
+    backend.unload_bank(fixture.unloaded_bank)?;
+    update_until(backend, "source bank to unload", || {
+        Ok(backend.get_event(fixture.unloaded_bank_event).is_err())
+    })?;
+    Ok(())
+}
+
+fn check_playback(backend: &dyn AudioBackend, fixture: &ConformanceFixture) -> AudioResult<()> {
+    let instance = backend
+        .get_event(fixture.looping_event)?
+        .create_instance()?;
+    assert_eq!(instance.get_playback_state()?, AudioPlaybackState::Stopped);
+    instance.set_3d_attributes(Vec2::new(1.0, 2.0), Vec2::new(0.5, 0.0))?;
+    instance.set_attributes_3d(&Audio3dAttributes {
+        position: Vec3::new(1.0, 2.0, 3.0),
+        velocity: Vec3::ZERO,
+        forward: Vec3::Z,
+        up: Vec3::Y,
+    })?;
+
+    assert!(
+        instance.poll_callbacks().is_err(),
+        "polling callbacks before enabling them should fail"
+    );
+    instance.enable_callbacks()?;
+    assert!(instance.poll_callbacks()?.is_empty());
+
+    instance.start()?;
+    assert_eq!(instance.get_playback_state()?, AudioPlaybackState::Starting);
+    update_until(backend, "instance to play", || {
+        Ok(instance.get_playback_state()? == AudioPlaybackState::Playing)
+    })?;
+    assert_eq!(
+        poll_lifecycle_callbacks_until(backend, &*instance, AudioEventCallback::Started)?,
+        vec![AudioEventCallback::Starting, AudioEventCallback::Started]
+    );
+
+    instance.stop(AudioStopMode::Immediate)?;
+    backend.update()?;
+    assert_eq!(instance.get_playback_state()?, AudioPlaybackState::Stopped);
+    assert_eq!(
+        poll_lifecycle_callbacks_until(backend, &*instance, AudioEventCallback::Stopped)?,
+        vec![AudioEventCallback::Stopped]
+    );
+    instance.release()?;
+    Ok(())
+}
+
+/// Immediate stops take effect by the next update, while fadeout stops go through
+/// [AudioPlaybackState::Stopping] (for as long as the event takes to fade out) first. `event_path`
+/// should be a looping event.
+pub fn check_stop_modes(backend: &dyn AudioBackend, event_path: &str) -> AudioResult<()> {
+    let description = backend.get_event(event_path)?;
+
+    let instance = description.create_instance()?;
+    instance.start()?;
+    backend.update()?;
+    instance.stop(AudioStopMode::Immediate)?;
+    backend.update()?;
+    assert_eq!(
+        instance.get_playback_state()?,
+        AudioPlaybackState::Stopped,
+        "immediate stop should be done after one update"
+    );
+    instance.release()?;
+
+    let instance = description.create_instance()?;
+    instance.start()?;
+    backend.update()?;
+    instance.stop(AudioStopMode::AllowFadeout)?;
+    let mut updates = 0;
+    loop {
+        backend.update()?;
+        match instance.get_playback_state()? {
+            AudioPlaybackState::Stopped => break,
+            AudioPlaybackState::Stopping => {}
+            state => panic!("instance should be stopping after a fadeout stop, got {state:?}"),
+        }
+        updates += 1;
+        assert!(updates < MAX_UPDATES, "fadeout stop never finished");
+    }
+    instance.release()?;
+
+    Ok(())
+}
+
+fn check_event_parameters(
+    backend: &dyn AudioBackend,
+    fixture: &ConformanceFixture,
+) -> AudioResult<()> {
+    let description = backend.get_event(fixture.parameter_event)?;
+    let parameters = description.parameters()?;
+    let Some(parameter) = parameters.iter().find(|p| p.name == fixture.parameter) else {
+        return Err(AudioError::other(format!(
+            "parameter {} isn't described",
+            fixture.parameter
+        )));
+    };
+    assert_eq!(parameter.kind, AudioParameterKind::GameControlled);
+    assert!(!parameter.flags.read_only && !parameter.flags.global);
+
+    let instance = description.create_instance()?;
+    assert_eq!(
+        instance.get_parameter_by_name(&parameter.name)?.value,
+        parameter.default_value,
+        "parameters should start at their default value"
+    );
+
+    instance.set_parameter_by_name(&parameter.name, parameter.maximum)?;
+    assert_eq!(
+        instance.get_parameter_by_name(&parameter.name)?.value,
+        parameter.maximum
+    );
+    instance.set_parameter_by_id(parameter.id, parameter.minimum)?;
+    assert_eq!(
+        instance.get_parameter_by_name(&parameter.name)?.value,
+        parameter.minimum
+    );
+    let handle = ParameterHandle::from(parameter);
+    instance.set_parameter_by_id(handle.id(), parameter.maximum)?;
+    assert_eq!(
+        instance.get_parameter_by_name(handle.name())?.value,
+        parameter.maximum
+    );
+
+    for parameter in parameters.iter().filter(|p| p.flags.labeled) {
+        let Some(label) = parameter.labels.last() else {
+            return Err(AudioError::other(format!(
+                "labeled parameter {} has no labels",
+                parameter.name
+            )));
+        };
+        let index = parameter.labels.len() - 1;
+        instance.set_parameter_by_name_with_label(&parameter.name, label)?;
+        assert_eq!(
+            instance.get_parameter_by_name(&parameter.name)?.value,
+            parameter.minimum + index as f32
+        );
+        assert!(
+            instance
+                .set_parameter_by_name_with_label(&parameter.name, "Does Not Exist")
+                .is_err()
+        );
+    }
+
+    assert!(
+        instance
+            .set_parameter_by_name("Does Not Exist", 1.0)
+            .is_err()
+    );
+    assert!(instance.get_parameter_by_name("Does Not Exist").is_err());
+    assert!(
+        instance
+            .set_parameter_by_name_with_label("Does Not Exist", "Does Not Exist")
+            .is_err()
+    );
+    instance.release()?;
+    Ok(())
+}
+
+fn check_global_parameters(
+    backend: &dyn AudioBackend,
+    fixture: &ConformanceFixture,
+) -> AudioResult<()> {
+    let parameters = backend.global_parameters()?;
+    assert!(parameters.iter().all(|p| p.flags.global));
+
+    if let Some(name) = fixture.global_parameter {
+        let Some(parameter) = parameters.iter().find(|p| p.name == name) else {
+            return Err(AudioError::other(format!(
+                "global parameter {name} isn't described"
+            )));
+        };
+        let handle = backend.get_parameter_handle(name)?;
+        assert_eq!(handle, ParameterHandle::from(parameter));
+        backend.set_parameter(&handle, parameter.maximum)?;
+        backend.set_parameter_by_name(name, parameter.minimum)?;
+    }
+
+    assert!(backend.get_parameter_handle("Does Not Exist").is_err());
+    assert!(
+        backend
+            .set_parameter_by_name("Does Not Exist", 1.0)
+            .is_err()
+    );
+    Ok(())
+}
+
+fn check_master_bus(backend: &dyn AudioBackend, fixture: &ConformanceFixture) -> AudioResult<()> {
+    assert!(backend.get_bus("bus:/Does Not Exist").is_err());
+
+    let bus = backend.get_bus("bus:/")?;
+    assert_eq!(bus.get_path()?, "bus:/");
+
+    bus.set_volume(0.5)?;
+    assert_eq!(bus.get_volume()?.volume, 0.5);
+    update_until(backend, "bus final volume to update", || {
+        Ok(bus.get_volume()?.final_volume == 0.5)
+    })?;
+    bus.set_volume(1.0)?;
+
+    bus.set_mute(true)?;
+    assert!(bus.get_mute()?);
+    bus.set_mute(false)?;
+    assert!(!bus.get_mute()?);
+
+    bus.set_paused(true)?;
+    assert!(bus.get_paused()?);
+    bus.set_paused(false)?;
+    assert!(!bus.get_paused()?);
+
+    let instance = backend
+        .get_event(fixture.looping_event)?
+        .create_instance()?;
+    instance.start()?;
+    update_until(backend, "instance to play", || {
+        Ok(instance.get_playback_state()? == AudioPlaybackState::Playing)
+    })?;
+    bus.stop_all_events(AudioStopMode::Immediate)?;
+    update_until(backend, "instance to be stopped by its bus", || {
+        Ok(instance.get_playback_state()? == AudioPlaybackState::Stopped)
+    })?;
+    instance.release()?;
+
+    bus.enable_metering()?;
+    let mut metering = None;
+    update_until(backend, "bus metering", || {
+        metering = bus.get_metering()?;
+        Ok(metering.is_some())
+    })?;
+    let metering = metering.unwrap();
+    assert_eq!(metering.peak_level.len(), metering.rms_level.len());
+    Ok(())
+}
+
+fn check_vcas(backend: &dyn AudioBackend, fixture: &ConformanceFixture) -> AudioResult<()> {
+    assert!(backend.get_vca("vca:/Does Not Exist").is_err());
+
+    let paths: Vec<_> = backend
+        .vca_list()?
+        .iter()
+        .map(|v| v.get_path())
+        .collect::<AudioResult<_>>()?;
+    let Some(path) = fixture.vca else {
+        return Ok(());
+    };
+    assert!(paths.iter().any(|p| p == path));
+
+    let vca = backend.get_vca(path)?;
+    assert_eq!(vca.get_path()?, path);
+    vca.set_volume(0.25)?;
+    assert_eq!(vca.get_volume()?.volume, 0.25);
+    update_until(backend, "VCA final volume to update", || {
+        Ok(vca.get_volume()?.final_volume == 0.25)
+    })?;
+    vca.set_volume(1.0)?;
+    Ok(())
+}
+
+fn check_listeners(backend: &dyn AudioBackend) -> AudioResult<()> {
+    let listener = |x| AudioListener {
+        weight: 1.0,
+        position: Vec2::new(x, 0.0),
+        velocity: Vec2::ZERO,
+        attenuation_position: None,
+    };
+
+    backend.set_listeners(&[listener(0.0)])?;
+    backend.set_listeners(&[listener(0.0), listener(10.0)])?;
+    // listeners past the maximum are ignored
+    let too_many: Vec<_> = (0..MAX_LISTENERS + 1).map(|i| listener(i as f32)).collect();
+    backend.set_listeners(&too_many)?;
+    backend.update()?;
+
+    backend.set_listeners_3d(&[AudioListener3d {
+        weight: 1.0,
+        attributes: Audio3dAttributes {
+            position: Vec3::new(0.0, 1.0, -5.0),
+            velocity: Vec3::ZERO,
+            forward: Vec3::Z,
+            up: Vec3::Y,
+        },
+        attenuation_position: None,
+    }])?;
+    backend.set_listeners(&[AudioListener {
+        attenuation_position: Some(Vec2::new(3.0, 4.0)),
+        ..listener(0.0)
+    }])?;
+    backend.update()?;
+
+    backend.set_3d_settings(2.0, 1.0, 0.5)?;
+    backend.set_3d_settings(1.0, 1.0, 1.0)?;
+    assert!(
+        matches!(
+            backend.set_3d_settings(1.0, 0.0, 1.0),
+            Err(AudioError::InvalidParameter { .. })
+        ),
+        "the distance factor should have to be positive"
+    );
+
+    assert!(
+        matches!(
+            backend.set_listeners(&[]),
+            Err(AudioError::InvalidParameter { .. })
+        ),
+        "there should always be at least one listener"
+    );
+    backend.set_listeners(&[listener(0.0)])?;
+
+    // fade a second listener in, then remove the first
+    backend.set_listeners(&[
+        listener(0.0),
+        AudioListener {
+            weight: 0.0,
+            ..listener(10.0)
+        },
+    ])?;
+    for step in 1..=4 {
+        let weight = step as f32 / 4.0;
+        backend.set_listener_weight(0, 1.0 - weight)?;
+        backend.set_listener_weight(1, weight)?;
+        backend.update()?;
+    }
+    backend.remove_listener(0)?;
+    backend.update()?;
+    assert!(
+        matches!(
+            backend.set_listener_weight(1, 1.0),
+            Err(AudioError::InvalidParameter { .. })
+        ),
+        "only one listener should be left"
+    );
+    assert!(
+        matches!(
+            backend.remove_listener(0),
+            Err(AudioError::InvalidParameter { .. })
+        ),
+        "the last listener shouldn't be removable"
+    );
+    Ok(())
+}
+
+/// Serves a single asset out of memory.
+struct MemoryAssetSource {
+    name: String,
+    data: Arc<[u8]>,
+}
+
+impl AudioAssetSource for MemoryAssetSource {
+    fn open(&self, name: &str) -> AudioResult<Box<dyn AudioAssetStream>> {
+        if name != self.name {
+            return Err(AudioError::other(format!("asset not found: {name}")));
+        }
+        Ok(Box::new(MemoryAssetStream(Cursor::new(self.data.clone()))))
+    }
+}
+
+struct MemoryAssetStream(Cursor<Arc<[u8]>>);
+
+impl AudioAssetStream for MemoryAssetStream {
+    fn size(&self) -> u64 {
+        self.0.get_ref().len() as u64
+    }
+
+    fn read(&mut self, buffer: &mut [u8]) -> AudioResult<usize> {
+        Ok(self.0.read(buffer)?)
+    }
+
+    fn seek(&mut self, position: u64) -> AudioResult<()> {
+        self.0.seek(SeekFrom::Start(position))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,259 @@
+//! Errors from the audio backends, structured so that callers can react to specific failures (e.g.
+//! falling back to another event if one isn't found) rather than just logging them.
+//!
+//! Errors which came from FMOD keep its `FMOD_RESULT` code, which is the same number on desktop and
+//! web. Failures which FMOD has no part in (e.g. the game asking for a bank it never loaded) have no
+//! code. The null backend uses the codes FMOD would have returned, so that the backends' errors
+//! match.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// An `FMOD_RESULT` value, e.g. [FMOD_ERR_EVENT_NOTFOUND].
+pub type FmodResultCode = i32;
+
+pub const FMOD_ERR_FILE_BAD: FmodResultCode = 13;
+pub const FMOD_ERR_FILE_NOTFOUND: FmodResultCode = 18;
+pub const FMOD_ERR_INITIALIZATION: FmodResultCode = 26;
+pub const FMOD_ERR_INVALID_FLOAT: FmodResultCode = 29;
+pub const FMOD_ERR_INVALID_HANDLE: FmodResultCode = 30;
+pub const FMOD_ERR_INVALID_PARAM: FmodResultCode = 31;
+pub const FMOD_ERR_INVALID_POSITION: FmodResultCode = 32;
+pub const FMOD_ERR_INVALID_VECTOR: FmodResultCode = 36;
+pub const FMOD_ERR_NOTREADY: FmodResultCode = 46;
+pub const FMOD_ERR_OUTPUT_ALLOCATED: FmodResultCode = 47;
+pub const FMOD_ERR_OUTPUT_CREATEBUFFER: FmodResultCode = 48;
+pub const FMOD_ERR_OUTPUT_DRIVERCALL: FmodResultCode = 49;
+pub const FMOD_ERR_OUTPUT_FORMAT: FmodResultCode = 50;
+pub const FMOD_ERR_OUTPUT_INIT: FmodResultCode = 51;
+pub const FMOD_ERR_OUTPUT_NODRIVERS: FmodResultCode = 52;
+pub const FMOD_ERR_UNINITIALIZED: FmodResultCode = 67;
+pub const FMOD_ERR_EVENT_ALREADY_LOADED: FmodResultCode = 70;
+pub const FMOD_ERR_EVENT_NOTFOUND: FmodResultCode = 74;
+pub const FMOD_ERR_STUDIO_UNINITIALIZED: FmodResultCode = 75;
+pub const FMOD_ERR_STUDIO_NOT_LOADED: FmodResultCode = 76;
+pub const FMOD_ERR_INVALID_STRING: FmodResultCode = 77;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AudioError {
+    /// No loaded bank has an event or snapshot with the given path.
+    EventNotFound {
+        message: String,
+        code: Option<FmodResultCode>,
+    },
+    /// The bank's file couldn't be found, or no bank was loaded from the given filename.
+    BankNotFound {
+        message: String,
+        code: Option<FmodResultCode>,
+    },
+    /// A bus, VCA, parameter or label couldn't be found.
+    NotFound {
+        message: String,
+        code: Option<FmodResultCode>,
+    },
+    /// The bank has already been loaded.
+    AlreadyLoaded {
+        message: String,
+        code: Option<FmodResultCode>,
+    },
+    /// A bank, event description or instance was used after its bank was unloaded (or after it was
+    /// released).
+    InvalidHandle {
+        message: String,
+        code: Option<FmodResultCode>,
+    },
+    /// Something needed for the call (the backend, a bank, a bus's channel group) isn't loaded.
+    NotLoaded {
+        message: String,
+        code: Option<FmodResultCode>,
+    },
+    /// FMOD couldn't start its output, e.g. because there's no audio device.
+    OutputInitFailed {
+        message: String,
+        code: Option<FmodResultCode>,
+    },
+    /// An argument wasn't valid for the call, e.g. setting a read-only parameter.
+    InvalidParameter {
+        message: String,
+        code: Option<FmodResultCode>,
+    },
+    /// Anything else, including errors from asset sources and trace files.
+    Other {
+        message: String,
+        code: Option<FmodResultCode>,
+    },
+}
+
+impl AudioError {
+    /// Classify an error code returned by FMOD. `FMOD_ERR_EVENT_NOTFOUND` is used for buses, VCAs
+    /// and parameters as well as events, so it becomes [AudioError::NotFound]; callers looking up
+    /// events turn that into [AudioError::EventNotFound] themselves.
+    pub fn from_fmod_code(code: FmodResultCode, message: impl Into<String>) -> Self {
+        let message = message.into();
+        match code {
+            FMOD_ERR_FILE_NOTFOUND => AudioError::BankNotFound {
+                message,
+                code: Some(code),
+            },
+            FMOD_ERR_EVENT_NOTFOUND => AudioError::NotFound {
+                message,
+                code: Some(code),
+            },
+            FMOD_ERR_EVENT_ALREADY_LOADED => AudioError::AlreadyLoaded {
+                message,
+                code: Some(code),
+            },
+            FMOD_ERR_INVALID_HANDLE => AudioError::InvalidHandle {
+                message,
+                code: Some(code),
+            },
+            FMOD_ERR_NOTREADY
+            | FMOD_ERR_UNINITIALIZED
+            | FMOD_ERR_STUDIO_UNINITIALIZED
+            | FMOD_ERR_STUDIO_NOT_LOADED => AudioError::NotLoaded {
+                message,
+                code: Some(code),
+            },
+            FMOD_ERR_INITIALIZATION
+            | FMOD_ERR_OUTPUT_ALLOCATED
+            | FMOD_ERR_OUTPUT_CREATEBUFFER
+            | FMOD_ERR_OUTPUT_DRIVERCALL
+            | FMOD_ERR_OUTPUT_FORMAT
+            | FMOD_ERR_OUTPUT_INIT
+            | FMOD_ERR_OUTPUT_NODRIVERS => AudioError::OutputInitFailed {
+                message,
+                code: Some(code),
+            },
+            FMOD_ERR_INVALID_FLOAT
+            | FMOD_ERR_INVALID_PARAM
+            | FMOD_ERR_INVALID_POSITION
+            | FMOD_ERR_INVALID_VECTOR
+            | FMOD_ERR_INVALID_STRING => AudioError::InvalidParameter {
+                message,
+                code: Some(code),
+            },
+            _ => AudioError::Other {
+                message,
+                code: Some(code),
+            },
+        }
+    }
+
+    /// An error which didn't come from FMOD and doesn't fit any other kind.
+    pub fn other(message: impl Into<String>) -> Self {
+        AudioError::Other {
+            message: message.into(),
+            code: None,
+        }
+    }
+
+    /// The `FMOD_RESULT` code, if this error came from FMOD.
+    pub fn code(&self) -> Option<FmodResultCode> {
+        match self {
+            AudioError::EventNotFound { code, .. }
+            | AudioError::BankNotFound { code, .. }
+            | AudioError::NotFound { code, .. }
+            | AudioError::AlreadyLoaded { code, .. }
+            | AudioError::InvalidHandle { code, .. }
+            | AudioError::NotLoaded { code, .. }
+            | AudioError::OutputInitFailed { code, .. }
+            | AudioError::InvalidParameter { code, .. }
+            | AudioError::Other { code, .. } => *code,
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            AudioError::EventNotFound { message, .. }
+            | AudioError::BankNotFound { message, .. }
+            | AudioError::NotFound { message, .. }
+            | AudioError::AlreadyLoaded { message, .. }
+            | AudioError::InvalidHandle { message, .. }
+            | AudioError::NotLoaded { message, .. }
+            | AudioError::OutputInitFailed { message, .. }
+            | AudioError::InvalidParameter { message, .. }
+            | AudioError::Other { message, .. } => message,
+        }
+    }
+
+    /// Turn an error from looking up `event_name` into [AudioError::EventNotFound] if FMOD couldn't
+    /// find it.
+    pub fn for_event(self, event_name: &str) -> Self {
+        match self {
+            AudioError::NotFound { code, .. } => AudioError::EventNotFound {
+                message: format!("Event not found: {event_name}"),
+                code,
+            },
+            e => e.context(format!("Getting event {event_name}")),
+        }
+    }
+
+    /// Prefix the message with what was being done when the error happened, keeping its kind and
+    /// code.
+    pub fn context(mut self, context: impl fmt::Display) -> Self {
+        match &mut self {
+            AudioError::EventNotFound { message, .. }
+            | AudioError::BankNotFound { message, .. }
+            | AudioError::NotFound { message, .. }
+            | AudioError::AlreadyLoaded { message, .. }
+            | AudioError::InvalidHandle { message, .. }
+            | AudioError::NotLoaded { message, .. }
+            | AudioError::OutputInitFailed { message, .. }
+            | AudioError::InvalidParameter { message, .. }
+            | AudioError::Other { message, .. } => *message = format!("{context}: {message}"),
+        }
+        self
+    }
+}
+
+impl fmt::Display for AudioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.code() {
+            Some(code) => write!(f, "{} (FMOD error {code})", self.message()),
+            None => write!(f, "{}", self.message()),
+        }
+    }
+}
+
+impl std::error::Error for AudioError {}
+
+impl From<std::io::Error> for AudioError {
+    fn from(e: std::io::Error) -> Self {
+        AudioError::other(e.to_string())
+    }
+}
+
+/// A name containing a nul byte, which can't be passed to FMOD.
+impl From<std::ffi::NulError> for AudioError {
+    fn from(e: std::ffi::NulError) -> Self {
+        AudioError::InvalidParameter {
+            message: e.to_string(),
+            code: None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for AudioError {
+    fn from(e: serde_json::Error) -> Self {
+        AudioError::other(e.to_string())
+    }
+}
+
+/// Adds context to errors on their way out, like anyhow's `Context` but keeping the
+/// [AudioError]'s kind and code intact.
+pub trait AudioResultExt<T> {
+    fn context(self, context: impl fmt::Display) -> Result<T, AudioError>;
+
+    fn with_context<C: fmt::Display>(self, context: impl FnOnce() -> C) -> Result<T, AudioError>;
+}
+
+impl<T, E: Into<AudioError>> AudioResultExt<T> for Result<T, E> {
+    fn context(self, context: impl fmt::Display) -> Result<T, AudioError> {
+        self.map_err(|e| e.into().context(context))
+    }
+
+    fn with_context<C: fmt::Display>(self, context: impl FnOnce() -> C) -> Result<T, AudioError> {
+        self.map_err(|e| e.into().context(context()))
+    }
+}
@@ -2,38 +2,53 @@
 //!
 //! Since loading on web needs to happen asynchronously (due to needing to instantiate fmod via a
 //! callback, and usually also waiting for audio files to be preloaded onto the emscripten
-//! filesystem), this API forces loading to happen asynchronously. Could be improved in the future
-//! by letting desktop loading happen synchronously and only making the loading asynchronous on web.
+//! filesystem), this API forces loading to happen asynchronously. Desktop works the same way:
+//! FMOD is initialized straight away and the banks are loaded in the background, with the loader
+//! checking on them each time it's polled.
 //!
 //! This wrapper also boxes everything to make it possible to move types as necessary (e.g.
 //! [AudioEventInstance::release] takes self by value to ensure that the instance is not again used
 //! later). There might be a neater way to do that instead.
 //!
-//! Lastly, this wrapper flattens banks into the system, which is just because in my game I only use
-//! one bank - a more flexible approach would be to expose banks as separate from the audiobackend
-//! (/fmod system).
+//! Banks are exposed separately from the backend via [AudioBank], and are identified by the
+//! filename they were loaded from (e.g. `SFX.bank`) since that works even when the strings bank
+//! (which is needed to look up `bank:/` paths) isn't loaded.
 #![allow(dead_code)]
 
+use std::sync::Arc;
+
 use crate::prelude::*;
 use serde::{Deserialize, Serialize};
 
 #[cfg(not(target_arch = "wasm32"))]
 mod backend_desktop;
+pub mod backend_null;
+pub mod backend_recording;
 #[cfg(target_arch = "wasm32")]
 mod backend_web;
+#[cfg(test)]
+mod conformance;
+pub mod error;
+pub mod snapshot;
+pub mod space;
+
+pub use error::{AudioError, AudioResultExt};
+pub use space::AudioSpaceConfig;
 
-pub type AudioResult<T> = anyhow::Result<T>;
+pub type AudioResult<T> = Result<T, AudioError>;
 
+/// `space` describes the game's coordinates, which positions and velocities are converted from.
 pub fn start_loading_audio_backend(
     banks_path: &str,
     bank_filenames: &[&str],
+    space: AudioSpaceConfig,
 ) -> Box<dyn AudioBackendLoader> {
     info!("Loading audio backend");
 
     #[cfg(target_arch = "wasm32")]
-    return backend_web::load_audio_backend(banks_path, bank_filenames);
+    return backend_web::load_audio_backend(banks_path, bank_filenames, space);
     #[cfg(not(target_arch = "wasm32"))]
-    return backend_desktop::load_audio_backend(banks_path, bank_filenames);
+    return backend_desktop::load_audio_backend(banks_path, bank_filenames, space);
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
@@ -45,14 +60,247 @@ pub enum AudioPlaybackState {
     Stopping,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum AudioLoadingState {
+    Unloading,
+    Unloaded,
+    Loading,
+    Loaded,
+    Error,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AudioStopMode {
+    /// Let AHDSR modulators and fades finish before stopping.
+    AllowFadeout,
+    Immediate,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AudioVolume {
+    /// The volume last set by the game.
+    pub volume: f32,
+    /// The volume after VCAs, snapshots and automation have been applied, as of the last update.
+    pub final_volume: f32,
+}
+
+/// Signal levels for each speaker channel of a bus's output, as linear gain.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AudioMeteringInfo {
+    pub peak_level: Vec<f32>,
+    pub rms_level: Vec<f32>,
+}
+
+/// FMOD supports up to this many listeners; any more passed to [AudioBackend::set_listeners] are
+/// ignored.
+pub const MAX_LISTENERS: usize = 8;
+
+/// A listener on the [AudioSpaceConfig::plane_2d], facing as described by [AudioSpaceConfig].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct AudioListener {
     pub weight: f32,
     pub position: Vec2,
     pub velocity: Vec2,
+    /// Where attenuation is calculated from, if not `position`; e.g. the player character, for a
+    /// camera listener. Panning still comes from `position`.
+    #[serde(default)]
+    pub attenuation_position: Option<Vec2>,
+}
+
+impl AudioListener {
+    pub fn to_3d(self, space: &AudioSpaceConfig) -> AudioListener3d {
+        AudioListener3d {
+            weight: self.weight,
+            attributes: space.attributes_from_2d(self.position, self.velocity),
+            attenuation_position: self.attenuation_position.map(|p| space.world_from_2d(p)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AudioListener3d {
+    pub weight: f32,
+    pub attributes: Audio3dAttributes,
+    /// As for [AudioListener::attenuation_position].
+    #[serde(default)]
+    pub attenuation_position: Option<Vec3>,
+}
+
+/// Where something is in the world and which way it's facing, in the game's coordinates (see
+/// [AudioSpaceConfig]).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Audio3dAttributes {
+    pub position: Vec3,
+    /// In world units per second.
+    pub velocity: Vec3,
+    /// Which way it's facing; must be perpendicular to `up`.
+    pub forward: Vec3,
+    pub up: Vec3,
+}
+
+/// Something that happened to an event instance, as reported by
+/// [AudioEventInstance::poll_callbacks]. Positions are in milliseconds along the event's timeline.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AudioEventCallback {
+    Starting,
+    Started,
+    Restarted,
+    Stopped,
+    /// The instance couldn't be started, e.g. because the event's max instances was reached.
+    StartFailed,
+    TimelineMarker {
+        name: String,
+        position: i32,
+    },
+    TimelineBeat {
+        bar: i32,
+        beat: i32,
+        position: i32,
+        tempo: f32,
+        time_signature_upper: i32,
+        time_signature_lower: i32,
+    },
+    SoundPlayed,
+    SoundStopped,
+    RealToVirtual,
+    VirtualToReal,
+}
+
+/// Identifies a parameter without needing to look it up by name each time it's set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct AudioParameterId {
+    pub data1: u32,
+    pub data2: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AudioParameterValue {
+    /// The value last set by the game.
+    pub value: f32,
+    /// The value after automation, modulation and seek speed have been applied, as of the last
+    /// update.
+    pub final_value: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AudioParameterKind {
+    GameControlled,
+    AutomaticDistance,
+    AutomaticEventConeAngle,
+    AutomaticEventOrientation,
+    AutomaticDirection,
+    AutomaticElevation,
+    AutomaticListenerOrientation,
+    AutomaticSpeed,
+    AutomaticSpeedAbsolute,
+    AutomaticDistanceNormalized,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AudioParameterFlags {
+    pub read_only: bool,
+    pub automatic: bool,
+    pub global: bool,
+    pub discrete: bool,
+    pub labeled: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AudioParameterDescription {
+    pub name: String,
+    pub id: AudioParameterId,
+    pub minimum: f32,
+    pub maximum: f32,
+    pub default_value: f32,
+    pub kind: AudioParameterKind,
+    pub flags: AudioParameterFlags,
+    /// For labeled parameters, the label of each value from `minimum` upwards. Empty otherwise.
+    pub labels: Vec<String>,
+}
+
+/// A global parameter which has been looked up once via [AudioBackend::get_parameter_handle], so
+/// that it can be set every frame without looking it up by name again.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParameterHandle {
+    name: String,
+    id: AudioParameterId,
+}
+
+impl ParameterHandle {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Can also be passed to [AudioEventInstance::set_parameter_by_id] if the handle was made from
+    /// one of the event's parameter descriptions.
+    pub fn id(&self) -> AudioParameterId {
+        self.id
+    }
+}
+
+impl From<&AudioParameterDescription> for ParameterHandle {
+    fn from(description: &AudioParameterDescription) -> Self {
+        ParameterHandle {
+            name: description.name.clone(),
+            id: description.id,
+        }
+    }
+}
+
+/// How far along loading the audio backend is.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LoadState {
+    /// Still loading, with progress from 0 to 1.
+    Pending(f32),
+    /// Loaded, so [AudioBackendLoader::get_loaded] will return the backend.
+    Ready,
+    /// Loading failed and won't be retried.
+    Failed(AudioError),
+}
+
+/// How much of a bank has been downloaded. `bytes_total` is 0 until the size is known.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BankDownloadProgress {
+    pub filename: String,
+    pub bytes_downloaded: u64,
+    pub bytes_total: u64,
+}
+
+/// Download progress of the banks the backend was asked to load, e.g. for drawing a progress bar.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct LoadProgress {
+    pub banks: Vec<BankDownloadProgress>,
+}
+
+impl LoadProgress {
+    pub fn bytes_downloaded(&self) -> u64 {
+        self.banks.iter().map(|b| b.bytes_downloaded).sum()
+    }
+
+    /// Only counts banks whose size is known, so this can grow as downloads start.
+    pub fn bytes_total(&self) -> u64 {
+        self.banks.iter().map(|b| b.bytes_total).sum()
+    }
+
+    /// Overall progress from 0 to 1.
+    pub fn fraction(&self) -> f32 {
+        let total = self.bytes_total();
+        if total == 0 {
+            return if self.banks.is_empty() { 1.0 } else { 0.0 };
+        }
+        (self.bytes_downloaded().min(total) as f64 / total as f64) as f32
+    }
 }
 
 pub trait AudioBackendLoader {
+    fn get_state(&self) -> LoadState;
+
+    /// Banks which don't need downloading (e.g. on desktop) are reported as fully downloaded.
+    fn get_progress(&self) -> LoadProgress;
+
+    /// Returns `None` while the backend is still loading, and the error if loading failed. The
+    /// backend is only returned once, so keep hold of it; later calls return an error rather than
+    /// another backend.
     fn get_loaded(&self) -> Option<AudioResult<Box<dyn AudioBackend>>>;
 }
 
@@ -65,20 +313,174 @@ pub trait AudioBackend {
 
     fn get_event_list(&self) -> AudioResult<Vec<Box<dyn AudioEventDescription>>>;
 
+    /// Get a loaded bank by the filename it was loaded from, e.g. `SFX.bank`.
+    fn get_bank(&self, filename: &str) -> AudioResult<Box<dyn AudioBank>>;
+
+    fn get_bank_list(&self) -> AudioResult<Vec<Box<dyn AudioBank>>>;
+
+    /// Start loading a bank in the background, relative to the path the backend was loaded from.
+    /// The bank only shows up in [AudioBackend::get_bank] and friends once it has finished loading.
+    fn load_bank(&self, filename: &str) -> AudioResult<Box<dyn AudioBankLoader>>;
+
+    /// Load a bank from a buffer in memory, e.g. one read out of an asset archive. `name` takes the
+    /// place of the filename when referring to the bank later. The data is copied, so the buffer
+    /// doesn't need to outlive this call.
+    fn load_bank_from_memory(&self, name: &str, data: &[u8]) -> AudioResult<Box<dyn AudioBank>>;
+
+    /// Load a bank whose data is read incrementally from `source` (e.g. a pak file, a zip, or an
+    /// in-memory cache), rather than from the filesystem or a single buffer. This suits large banks
+    /// with streamed sounds, since their data is read on demand rather than all up front; `source`
+    /// is kept until the bank is unloaded.
+    fn load_bank_from_source(
+        &self,
+        name: &str,
+        source: Arc<dyn AudioAssetSource>,
+    ) -> AudioResult<Box<dyn AudioBank>>;
+
+    /// Unload a bank by the filename it was loaded from.
+    fn unload_bank(&self, filename: &str) -> AudioResult<()>;
+
+    /// Get a mixer bus by path, e.g. `bus:/SFX` (or `bus:/` for the master bus).
+    fn get_bus(&self, path: &str) -> AudioResult<Box<dyn AudioBus>>;
+
+    /// Get a VCA by path, e.g. `vca:/Music`.
+    fn get_vca(&self, path: &str) -> AudioResult<Box<dyn AudioVca>>;
+
+    /// All VCAs in the loaded banks.
+    fn vca_list(&self) -> AudioResult<Vec<Box<dyn AudioVca>>>;
+
+    /// Set where the game is listening from. There must be at least one listener.
     fn set_listeners(&self, listeners: &[AudioListener]) -> AudioResult<()>;
 
+    /// Like [AudioBackend::set_listeners], but with full 3D positions and orientations.
+    fn set_listeners_3d(&self, listeners: &[AudioListener3d]) -> AudioResult<()>;
+
+    /// Change the weight of one of the listeners last set, from 0 (no effect on the mix) to 1.
+    /// Call this every frame to fade a listener in or out, e.g. when a split-screen player joins
+    /// or leaves.
+    fn set_listener_weight(&self, index: usize, weight: f32) -> AudioResult<()>;
+
+    /// Remove one of the listeners last set, moving any later listeners down. There must still be
+    /// at least one listener afterwards.
+    fn remove_listener(&self, index: usize) -> AudioResult<()>;
+
+    /// Set the global 3D scales, which all default to 1: `doppler_scale` exaggerates or dampens
+    /// the doppler effect, `distance_factor` is how many FMOD units make a metre, and
+    /// `rolloff_scale` speeds up or slows down distance attenuation. Positions are already in
+    /// metres (see [AudioSpaceConfig::units_per_metre]), so `distance_factor` should normally be
+    /// left at 1.
+    fn set_3d_settings(
+        &self,
+        doppler_scale: f32,
+        distance_factor: f32,
+        rolloff_scale: f32,
+    ) -> AudioResult<()>;
+
     fn set_parameter_by_name(&self, name: &str, value: f32) -> AudioResult<()>;
+
+    /// Descriptions of all global parameters in the loaded banks.
+    fn global_parameters(&self) -> AudioResult<Vec<AudioParameterDescription>>;
+
+    /// Look up a global parameter by name, for use with [AudioBackend::set_parameter].
+    fn get_parameter_handle(&self, name: &str) -> AudioResult<ParameterHandle>;
+
+    fn set_parameter(&self, handle: &ParameterHandle, value: f32) -> AudioResult<()>;
+}
+
+/// Somewhere bank data can be read from, for [AudioBackend::load_bank_from_source].
+///
+/// FMOD may read from streams on its own threads, hence the `Send`/`Sync` bounds.
+pub trait AudioAssetSource: Send + Sync {
+    /// Open the asset with the given name for reading.
+    fn open(&self, name: &str) -> AudioResult<Box<dyn AudioAssetStream>>;
+}
+
+pub trait AudioAssetStream: Send {
+    /// Total size of the asset in bytes.
+    fn size(&self) -> u64;
+    /// Read into `buffer` from the current position, returning how many bytes were read; 0 means
+    /// the end of the asset has been reached.
+    fn read(&mut self, buffer: &mut [u8]) -> AudioResult<usize>;
+    /// Move the current position to `position` bytes from the start of the asset.
+    fn seek(&mut self, position: u64) -> AudioResult<()>;
+}
+
+pub trait AudioBankLoader {
+    /// How far along loading the bank is, from 0 to 1. This is 1 once loading has finished,
+    /// including when it failed, so check [AudioBankLoader::get_loaded] for the outcome.
+    fn get_progress(&self) -> f32;
+
+    /// Returns `None` while the bank is still loading.
+    fn get_loaded(&self) -> Option<AudioResult<Box<dyn AudioBank>>>;
+}
+
+pub trait AudioBank {
+    /// The filename this bank was loaded from, e.g. `SFX.bank`.
+    fn get_filename(&self) -> String;
+    /// The `bank:/` path of this bank; only available if the strings bank is loaded.
+    fn get_path(&self) -> AudioResult<String>;
+    /// The GUID of this bank, formatted like `{01234567-89ab-cdef-0123-456789abcdef}`.
+    fn get_id(&self) -> AudioResult<String>;
+    fn get_loading_state(&self) -> AudioResult<AudioLoadingState>;
+    fn get_sample_loading_state(&self) -> AudioResult<AudioLoadingState>;
+    fn load_sample_data(&self) -> AudioResult<()>;
+    fn unload_sample_data(&self) -> AudioResult<()>;
+    fn get_event_list(&self) -> AudioResult<Vec<Box<dyn AudioEventDescription>>>;
+    /// Unload the bank, invalidating all event descriptions and instances that came from it.
+    fn unload(self: Box<Self>) -> AudioResult<()>;
 }
 
 pub trait AudioEventDescription {
     fn create_instance(&self) -> AudioResult<Box<dyn AudioEventInstance>>;
     fn get_path(&self) -> AudioResult<String>;
+    /// Descriptions of all the parameters used by the event, including global ones.
+    fn parameters(&self) -> AudioResult<Vec<AudioParameterDescription>>;
+    /// Whether the event is a mix snapshot rather than a regular event; see [snapshot].
+    fn is_snapshot(&self) -> AudioResult<bool>;
 }
 
 pub trait AudioEventInstance {
     fn release(self: Box<Self>) -> AudioResult<()>;
     fn start(&self) -> AudioResult<()>;
-    fn stop(&self) -> AudioResult<()>;
+    fn stop(&self, mode: AudioStopMode) -> AudioResult<()>;
+    /// Position the instance on the [AudioSpaceConfig::plane_2d], facing the same way as 2D
+    /// listeners.
     fn set_3d_attributes(&self, position: Vec2, velocity: Vec2) -> AudioResult<()>;
+    fn set_attributes_3d(&self, attributes: &Audio3dAttributes) -> AudioResult<()>;
     fn get_playback_state(&self) -> AudioResult<AudioPlaybackState>;
+    fn set_parameter_by_name(&self, name: &str, value: f32) -> AudioResult<()>;
+    fn set_parameter_by_id(&self, id: AudioParameterId, value: f32) -> AudioResult<()>;
+    /// Set a labeled parameter to the value with the given label.
+    fn set_parameter_by_name_with_label(&self, name: &str, label: &str) -> AudioResult<()>;
+    fn get_parameter_by_name(&self, name: &str) -> AudioResult<AudioParameterValue>;
+    /// Start queueing up [AudioEventCallback]s for this instance. They're off by default since
+    /// most instances are fire-and-forget.
+    fn enable_callbacks(&self) -> AudioResult<()>;
+    /// Take the callbacks which have fired since the last poll, oldest first. FMOD fires them while
+    /// processing [AudioBackend::update], so this is best called after that.
+    fn poll_callbacks(&self) -> AudioResult<Vec<AudioEventCallback>>;
+}
+
+pub trait AudioBus {
+    fn get_path(&self) -> AudioResult<String>;
+    fn set_volume(&self, volume: f32) -> AudioResult<()>;
+    fn get_volume(&self) -> AudioResult<AudioVolume>;
+    fn set_mute(&self, mute: bool) -> AudioResult<()>;
+    fn get_mute(&self) -> AudioResult<bool>;
+    fn set_paused(&self, paused: bool) -> AudioResult<()>;
+    fn get_paused(&self) -> AudioResult<bool>;
+    /// Stop all event instances routed into this bus.
+    fn stop_all_events(&self, mode: AudioStopMode) -> AudioResult<()>;
+    /// Keep the bus's channel group around even while nothing is playing through it, and meter its
+    /// output. Levels are available via [AudioBus::get_metering] from the next update.
+    fn enable_metering(&self) -> AudioResult<()>;
+    /// The bus's output levels, or `None` if its channel group hasn't been created yet or nothing
+    /// has been metered since. Keep calling it after updates until the levels turn up.
+    fn get_metering(&self) -> AudioResult<Option<AudioMeteringInfo>>;
+}
+
+pub trait AudioVca {
+    fn get_path(&self) -> AudioResult<String>;
+    fn set_volume(&self, volume: f32) -> AudioResult<()>;
+    fn get_volume(&self) -> AudioResult<AudioVolume>;
 }
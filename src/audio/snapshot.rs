@@ -0,0 +1,262 @@
+//! Mix snapshots (pause menu, underwater, low health etc.) on top of [AudioEventInstance].
+//!
+//! Snapshots are just events as far as FMOD is concerned, with a built-in `Intensity` parameter
+//! controlling how strongly they're applied. [AudioSnapshotStack] lets game states push a snapshot
+//! when they're entered and pop it again when they're left.
+
+use super::*;
+
+/// Name of the parameter FMOD Studio gives every snapshot.
+const INTENSITY_PARAMETER: &str = "Intensity";
+
+/// An instance of a snapshot event.
+pub struct AudioSnapshot {
+    path: String,
+    instance: Box<dyn AudioEventInstance>,
+}
+
+impl AudioSnapshot {
+    /// Create a (stopped) instance of a snapshot, failing if the event isn't a snapshot.
+    pub fn new(description: &dyn AudioEventDescription) -> AudioResult<Self> {
+        let path = description.get_path()?;
+        if !description.is_snapshot()? {
+            return Err(AudioError::InvalidParameter {
+                message: format!("Event is not a snapshot: {path}"),
+                code: None,
+            });
+        }
+
+        Ok(AudioSnapshot {
+            instance: description.create_instance()?,
+            path,
+        })
+    }
+
+    pub fn get_path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn start(&self) -> AudioResult<()> {
+        self.instance.start()
+    }
+
+    pub fn stop(&self, mode: AudioStopMode) -> AudioResult<()> {
+        self.instance.stop(mode)
+    }
+
+    /// Set how strongly the snapshot is applied, as a percentage (0-100) like in FMOD Studio.
+    pub fn set_intensity(&self, intensity: f32) -> AudioResult<()> {
+        self.instance
+            .set_parameter_by_name(INTENSITY_PARAMETER, intensity)
+    }
+
+    pub fn get_intensity(&self) -> AudioResult<AudioParameterValue> {
+        self.instance.get_parameter_by_name(INTENSITY_PARAMETER)
+    }
+
+    pub fn get_playback_state(&self) -> AudioResult<AudioPlaybackState> {
+        self.instance.get_playback_state()
+    }
+
+    /// Stop the snapshot, letting it fade out, and release its instance. The instance is released
+    /// even if stopping it fails, and the first error is returned.
+    pub fn release(self) -> AudioResult<()> {
+        let stopped = self.instance.stop(AudioStopMode::AllowFadeout);
+        let released = self.instance.release();
+        stopped
+            .and(released)
+            .with_context(|| format!("Releasing snapshot {}", self.path))
+    }
+}
+
+/// Snapshots pushed by game states, most recent last. Each push starts a new instance, so the same
+/// snapshot can be pushed by several states at once and stays applied until they've all popped it.
+#[derive(Default)]
+pub struct AudioSnapshotStack {
+    snapshots: Vec<AudioSnapshot>,
+}
+
+impl AudioSnapshotStack {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start the snapshot at `path` (e.g. `snapshot:/Underwater`) and put it on top of the stack.
+    pub fn push(&mut self, backend: &dyn AudioBackend, path: &str) -> AudioResult<()> {
+        let snapshot = AudioSnapshot::new(backend.get_event(path)?.as_ref())?;
+        snapshot.start()?;
+        self.snapshots.push(snapshot);
+        Ok(())
+    }
+
+    /// Stop and release the snapshot on top of the stack, returning its path. Does nothing if the
+    /// stack is empty. If the snapshot can't be stopped, it's left on the stack.
+    pub fn pop(&mut self) -> AudioResult<Option<String>> {
+        let Some(snapshot) = self.snapshots.last() else {
+            return Ok(None);
+        };
+        snapshot
+            .stop(AudioStopMode::AllowFadeout)
+            .with_context(|| format!("Stopping snapshot {}", snapshot.path))?;
+
+        let snapshot = self.snapshots.pop().expect("checked above");
+        snapshot
+            .instance
+            .release()
+            .with_context(|| format!("Releasing snapshot {}", snapshot.path))?;
+        Ok(Some(snapshot.path))
+    }
+
+    /// The snapshot on top of the stack, e.g. to adjust its intensity.
+    pub fn top(&self) -> Option<&AudioSnapshot> {
+        self.snapshots.last()
+    }
+
+    /// Whether any instance of the snapshot at `path` is on the stack.
+    pub fn contains(&self, path: &str) -> bool {
+        self.snapshots.iter().any(|s| s.path == path)
+    }
+
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+
+    /// Stop and release every snapshot on the stack, e.g. when returning to the main menu. Every
+    /// snapshot is tried even if some fail, so that none are left applied, and the first error is
+    /// returned.
+    pub fn clear(&mut self) -> AudioResult<()> {
+        let mut result = Ok(());
+        while let Some(snapshot) = self.snapshots.pop() {
+            let released = snapshot.release();
+            if result.is_ok() {
+                result = released;
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use super::backend_null::NullAudioBackend;
+    use super::*;
+
+    const UNDERWATER: &str = "snapshot:/Underwater";
+    const PAUSED: &str = "snapshot:/Paused";
+
+    /// A null instance which fails to stop and release while `failing` is set.
+    struct FlakyInstance {
+        inner: Box<dyn AudioEventInstance>,
+        failing: Rc<Cell<bool>>,
+    }
+
+    impl FlakyInstance {
+        fn check(&self) -> AudioResult<()> {
+            if self.failing.get() {
+                return Err(AudioError::other("flaky instance failed"));
+            }
+            Ok(())
+        }
+    }
+
+    impl AudioEventInstance for FlakyInstance {
+        fn release(self: Box<Self>) -> AudioResult<()> {
+            self.check()?;
+            self.inner.release()
+        }
+        fn start(&self) -> AudioResult<()> {
+            self.inner.start()
+        }
+        fn stop(&self, mode: AudioStopMode) -> AudioResult<()> {
+            self.check()?;
+            self.inner.stop(mode)
+        }
+        fn set_3d_attributes(&self, position: Vec2, velocity: Vec2) -> AudioResult<()> {
+            self.inner.set_3d_attributes(position, velocity)
+        }
+        fn set_attributes_3d(&self, attributes: &Audio3dAttributes) -> AudioResult<()> {
+            self.inner.set_attributes_3d(attributes)
+        }
+        fn get_playback_state(&self) -> AudioResult<AudioPlaybackState> {
+            self.inner.get_playback_state()
+        }
+        fn set_parameter_by_name(&self, name: &str, value: f32) -> AudioResult<()> {
+            self.inner.set_parameter_by_name(name, value)
+        }
+        fn set_parameter_by_id(&self, id: AudioParameterId, value: f32) -> AudioResult<()> {
+            self.inner.set_parameter_by_id(id, value)
+        }
+        fn set_parameter_by_name_with_label(&self, name: &str, label: &str) -> AudioResult<()> {
+            self.inner.set_parameter_by_name_with_label(name, label)
+        }
+        fn get_parameter_by_name(&self, name: &str) -> AudioResult<AudioParameterValue> {
+            self.inner.get_parameter_by_name(name)
+        }
+        fn enable_callbacks(&self) -> AudioResult<()> {
+            self.inner.enable_callbacks()
+        }
+        fn poll_callbacks(&self) -> AudioResult<Vec<AudioEventCallback>> {
+            self.inner.poll_callbacks()
+        }
+    }
+
+    /// Push a started snapshot whose instance fails while the returned flag is set.
+    fn push_flaky(
+        stack: &mut AudioSnapshotStack,
+        backend: &NullAudioBackend,
+        path: &str,
+    ) -> Rc<Cell<bool>> {
+        let failing = Rc::new(Cell::new(false));
+        let snapshot = AudioSnapshot {
+            path: path.to_owned(),
+            instance: Box::new(FlakyInstance {
+                inner: backend.get_event(path).unwrap().create_instance().unwrap(),
+                failing: failing.clone(),
+            }),
+        };
+        snapshot.start().unwrap();
+        stack.snapshots.push(snapshot);
+        failing
+    }
+
+    #[test]
+    fn failed_pop_leaves_the_snapshot_on_the_stack() {
+        let backend = NullAudioBackend::new(&[UNDERWATER]);
+        let mut stack = AudioSnapshotStack::new();
+        let failing = push_flaky(&mut stack, &backend, UNDERWATER);
+
+        failing.set(true);
+        let error = stack.pop().unwrap_err();
+        assert!(error.message().contains(UNDERWATER), "{error}");
+        assert_eq!(stack.len(), 1);
+
+        failing.set(false);
+        assert_eq!(stack.pop().unwrap().as_deref(), Some(UNDERWATER));
+        assert!(stack.is_empty());
+    }
+
+    #[test]
+    fn clear_releases_everything_despite_failures() {
+        let backend = NullAudioBackend::new(&[UNDERWATER, PAUSED]);
+        let inspector = backend.inspector();
+        let mut stack = AudioSnapshotStack::new();
+        stack.push(&backend, PAUSED).unwrap();
+        let failing = push_flaky(&mut stack, &backend, UNDERWATER);
+        backend.update().unwrap();
+
+        failing.set(true);
+        let error = stack.clear().unwrap_err();
+        assert!(error.message().contains(UNDERWATER), "{error}");
+        assert!(stack.is_empty());
+        // the snapshot below the failing one was still stopped and released
+        assert_eq!(inspector.playing_count(PAUSED), 0);
+        assert!(inspector.instances_of(PAUSED)[0].released);
+    }
+}
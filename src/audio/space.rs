@@ -0,0 +1,257 @@
+//! How the game's coordinates map onto FMOD's, so that each game can describe its own world rather
+//! than the backends assuming one.
+//!
+//! FMOD uses left-handed coordinates in metres, with +y up and +z forward by default. The backends
+//! convert positions and velocities from world units to metres and flip z for right-handed worlds.
+//! 2D positions are first placed on [AudioSpaceConfig::plane_2d], and 2D listeners and instances
+//! face so that the world's +x axis is to their right and [AudioSpaceConfig::up] is up.
+
+use glam::{Vec2, Vec3};
+use serde::{Deserialize, Serialize};
+
+use super::{Audio3dAttributes, AudioError, AudioResult};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Handedness {
+    Left,
+    Right,
+}
+
+/// The plane which 2D positions lie on in the 3D world.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Plane2d {
+    /// 2D (x, y) becomes (x, y, 0), e.g. for side-on games.
+    XY,
+    /// 2D (x, y) becomes (x, 0, y), e.g. for top-down games with y up.
+    XZ,
+}
+
+/// Passed when loading the backend, and applied the same way by every backend. Loading fails with
+/// [AudioError::InvalidParameter] if the config doesn't pass [AudioSpaceConfig::validate].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AudioSpaceConfig {
+    /// How many world units make a metre; must be positive.
+    pub units_per_metre: f32,
+    pub handedness: Handedness,
+    /// The world's up direction, which must be perpendicular to the x axis.
+    pub up: Vec3,
+    pub plane_2d: Plane2d,
+}
+
+impl Default for AudioSpaceConfig {
+    /// FMOD's own coordinates: metres, left-handed, +y up.
+    fn default() -> Self {
+        AudioSpaceConfig {
+            units_per_metre: 1.0,
+            handedness: Handedness::Left,
+            up: Vec3::Y,
+            plane_2d: Plane2d::XY,
+        }
+    }
+}
+
+impl AudioSpaceConfig {
+    /// Screen coordinates for 2D games: x runs right and y runs down the screen, which makes the
+    /// world right-handed with z going into the screen.
+    pub fn screen_pixels(pixels_per_metre: f32) -> Self {
+        AudioSpaceConfig {
+            units_per_metre: pixels_per_metre,
+            handedness: Handedness::Right,
+            up: Vec3::NEG_Y,
+            plane_2d: Plane2d::XY,
+        }
+    }
+
+    /// Check the requirements on `units_per_metre` and `up`, which the conversions below rely on.
+    pub fn validate(&self) -> AudioResult<()> {
+        let invalid = |message: String| AudioError::InvalidParameter {
+            message,
+            code: None,
+        };
+        if !(self.units_per_metre.is_finite() && self.units_per_metre > 0.0) {
+            return Err(invalid(format!(
+                "units_per_metre must be positive, not {}",
+                self.units_per_metre
+            )));
+        }
+        let up = self.up.normalize_or_zero();
+        if up == Vec3::ZERO {
+            return Err(invalid(format!(
+                "up must be a non-zero direction, not {}",
+                self.up
+            )));
+        }
+        if up.x.abs() > 1e-4 {
+            return Err(invalid(format!(
+                "up must be perpendicular to the x axis, not {}",
+                self.up
+            )));
+        }
+        Ok(())
+    }
+
+    /// Place a 2D position or velocity in the 3D world.
+    pub fn world_from_2d(&self, v: Vec2) -> Vec3 {
+        match self.plane_2d {
+            Plane2d::XY => Vec3::new(v.x, v.y, 0.0),
+            Plane2d::XZ => Vec3::new(v.x, 0.0, v.y),
+        }
+    }
+
+    /// Convert a world direction to FMOD's handedness, without scaling it.
+    pub fn fmod_direction(&self, v: Vec3) -> Vec3 {
+        match self.handedness {
+            Handedness::Left => v,
+            Handedness::Right => Vec3::new(v.x, v.y, -v.z),
+        }
+    }
+
+    /// Convert a world position or velocity to FMOD's coordinates, in metres.
+    pub fn fmod_position(&self, v: Vec3) -> Vec3 {
+        self.fmod_direction(v) / self.units_per_metre
+    }
+
+    /// The world forward and up vectors for 2D listeners and instances.
+    pub fn orientation_2d(&self) -> (Vec3, Vec3) {
+        let up = self.up.normalize();
+        // with x to the right, forward is x cross up in left-handed coordinates, and the other way
+        // round in right-handed ones
+        let forward = match self.handedness {
+            Handedness::Left => Vec3::X.cross(up),
+            Handedness::Right => up.cross(Vec3::X),
+        };
+        (forward, up)
+    }
+
+    /// The 3D attributes for a 2D position and velocity.
+    pub fn attributes_from_2d(&self, position: Vec2, velocity: Vec2) -> Audio3dAttributes {
+        let (forward, up) = self.orientation_2d();
+        Audio3dAttributes {
+            position: self.world_from_2d(position),
+            velocity: self.world_from_2d(velocity),
+            forward,
+            up,
+        }
+    }
+
+    /// Convert world attributes to FMOD's coordinates.
+    pub fn fmod_attributes(&self, attributes: &Audio3dAttributes) -> Audio3dAttributes {
+        Audio3dAttributes {
+            position: self.fmod_position(attributes.position),
+            velocity: self.fmod_position(attributes.velocity),
+            forward: self.fmod_direction(attributes.forward),
+            up: self.fmod_direction(attributes.up),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// FMOD is left-handed, so the listener's right is `up` cross `forward`.
+    fn fmod_right(space: &AudioSpaceConfig) -> Vec3 {
+        let attributes = space.fmod_attributes(&space.attributes_from_2d(Vec2::ZERO, Vec2::ZERO));
+        attributes.up.cross(attributes.forward)
+    }
+
+    #[test]
+    fn screen_pixels_faces_into_the_screen() {
+        let space = AudioSpaceConfig::screen_pixels(10.0);
+        space.validate().unwrap();
+
+        let attributes = space.fmod_attributes(
+            &space.attributes_from_2d(Vec2::new(20.0, 30.0), Vec2::new(-10.0, 0.0)),
+        );
+        // x runs right in metres, and y down the screen is away from `up`
+        assert_eq!(attributes.position, Vec3::new(2.0, 3.0, 0.0));
+        assert_eq!(attributes.velocity, Vec3::new(-1.0, 0.0, 0.0));
+        assert_eq!(attributes.up, Vec3::NEG_Y);
+        assert_eq!(attributes.forward, Vec3::NEG_Z);
+        assert_eq!(fmod_right(&space), Vec3::X);
+    }
+
+    #[test]
+    fn default_is_fmods_own_coordinates() {
+        let space = AudioSpaceConfig::default();
+        let attributes = Audio3dAttributes {
+            position: Vec3::new(1.0, 2.0, 3.0),
+            velocity: Vec3::new(4.0, 5.0, 6.0),
+            forward: Vec3::Z,
+            up: Vec3::Y,
+        };
+        assert_eq!(space.fmod_attributes(&attributes), attributes);
+        assert_eq!(space.orientation_2d(), (Vec3::Z, Vec3::Y));
+        assert_eq!(fmod_right(&space), Vec3::X);
+    }
+
+    #[test]
+    fn xz_plane_puts_2d_y_on_z() {
+        let space = AudioSpaceConfig {
+            plane_2d: Plane2d::XZ,
+            ..Default::default()
+        };
+        let attributes = space.attributes_from_2d(Vec2::new(1.0, 2.0), Vec2::new(3.0, 4.0));
+        assert_eq!(attributes.position, Vec3::new(1.0, 0.0, 2.0));
+        assert_eq!(attributes.velocity, Vec3::new(3.0, 0.0, 4.0));
+        assert_eq!(fmod_right(&space), Vec3::X);
+    }
+
+    #[test]
+    fn right_handed_worlds_flip_z() {
+        let space = AudioSpaceConfig {
+            units_per_metre: 2.0,
+            handedness: Handedness::Right,
+            ..Default::default()
+        };
+        let v = Vec3::new(2.0, 4.0, 6.0);
+        assert_eq!(space.fmod_direction(v), Vec3::new(2.0, 4.0, -6.0));
+        assert_eq!(space.fmod_position(v), Vec3::new(1.0, 2.0, -3.0));
+        // forward flips along with everything else, so x stays on the right
+        assert_eq!(space.orientation_2d(), (Vec3::NEG_Z, Vec3::Y));
+        assert_eq!(fmod_right(&space), Vec3::X);
+    }
+
+    #[test]
+    fn validate_rejects_unusable_configs() {
+        let invalid = [
+            AudioSpaceConfig {
+                units_per_metre: 0.0,
+                ..Default::default()
+            },
+            AudioSpaceConfig {
+                units_per_metre: -1.0,
+                ..Default::default()
+            },
+            AudioSpaceConfig {
+                units_per_metre: f32::NAN,
+                ..Default::default()
+            },
+            AudioSpaceConfig {
+                up: Vec3::ZERO,
+                ..Default::default()
+            },
+            AudioSpaceConfig {
+                up: Vec3::NEG_X,
+                ..Default::default()
+            },
+            AudioSpaceConfig {
+                up: Vec3::new(1.0, 1.0, 0.0),
+                ..Default::default()
+            },
+        ];
+        for space in invalid {
+            assert!(
+                matches!(space.validate(), Err(AudioError::InvalidParameter { .. })),
+                "{space:?} should be invalid"
+            );
+        }
+        // up doesn't need to be normalized
+        AudioSpaceConfig {
+            up: Vec3::new(0.0, 0.0, -5.0),
+            ..Default::default()
+        }
+        .validate()
+        .unwrap();
+    }
+}
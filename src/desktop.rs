@@ -4,12 +4,19 @@ use crate::prelude::*;
 pub fn run(studio_examples_path: &str) -> anyhow::Result<()> {
     let bank_files = vec!["Master.bank", "Master.strings.bank", "SFX.bank"];
 
-    let audio_loader = start_loading_audio_backend(studio_examples_path, &bank_files);
-
-    // Wait for audio backend to be loaded
-    let audio_backend = match audio_loader.get_loaded() {
-        Some(result) => result?,
-        None => return Err(anyhow::anyhow!("Failed to load audio backend")),
+    // 10 pixels to a metre, as in the game this was written for
+    let audio_loader = start_loading_audio_backend(
+        studio_examples_path,
+        &bank_files,
+        AudioSpaceConfig::screen_pixels(10.0),
+    );
+
+    // Wait for audio backend to be loaded; a game would keep drawing a loading screen meanwhile
+    let audio_backend = loop {
+        if let Some(result) = audio_loader.get_loaded() {
+            break result?;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(16));
     };
 
     let explosion = audio_backend.get_event("event:/Weapons/Explosion")?;
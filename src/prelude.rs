@@ -1,6 +1,6 @@
 #![allow(unused)]
 
-pub use anyhow::{Context, bail};
-pub use glam::Vec2;
+pub use anyhow::bail;
+pub use glam::{Vec2, Vec3};
 pub use log::{debug, error, info, trace, warn};
 pub use serde::{Deserialize, Serialize};
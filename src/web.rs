@@ -14,7 +14,13 @@ extern "C" {
 pub fn run() -> Result<(), JsValue> {
     let banks = vec!["Master.bank", "Master.strings.bank", "SFX.bank"];
 
-    let fmod_loader = crate::audio::start_loading_audio_backend("/assets/", &banks);
+    // 10 pixels to a metre, as in the game this was written for
+    let fmod_loader = crate::audio::start_loading_audio_backend(
+        "/assets/",
+        &banks,
+        AudioSpaceConfig::screen_pixels(10.0),
+    );
+    let mut audio = AudioStatus::Loading(fmod_loader);
 
     let f = Rc::new(RefCell::new(None));
     let g = f.clone();
@@ -35,37 +41,58 @@ pub fn run() -> Result<(), JsValue> {
         // Set the body's text content to how many times this
         // requestAnimationFrame callback has fired.
         i += 1;
-        let text = format!("requestAnimationFrame has been called {} times.", i);
+        let mut text = format!("requestAnimationFrame has been called {} times.", i);
+        if let AudioStatus::Loading(fmod_loader) = &audio
+            && let LoadState::Pending(fraction) = fmod_loader.get_state()
+        {
+            let progress = fmod_loader.get_progress();
+            text += &format!(
+                " Loading audio: {} / {} KB ({:.0}%)",
+                progress.bytes_downloaded() / 1024,
+                progress.bytes_total() / 1024,
+                fraction * 100.0
+            );
+        }
         body().set_text_content(Some(&text));
 
         // Schedule ourself for another requestAnimationFrame callback.
         request_animation_frame(f.borrow().as_ref().unwrap());
 
-        if let Some(load_result) = fmod_loader.get_loaded() {
-            match load_result {
-                Ok(fmod_web) => match handle_audio(fmod_web, &mut events, i) {
-                    Ok(_) => (),
-                    Err(e) => {
-                        console_log(&format!("Audio error: {:?}", e));
-                    }
-                },
-                Err(e) => {
+        // the loader only hands out the backend once, so hold on to it
+        if let AudioStatus::Loading(fmod_loader) = &audio {
+            match fmod_loader.get_loaded() {
+                Some(Ok(fmod_web)) => audio = AudioStatus::Loaded(fmod_web),
+                Some(Err(e)) => {
                     console_log(&format!("Audio loading error: {:?}", e));
+                    audio = AudioStatus::Failed;
                 }
+                None => (),
             }
         }
+
+        if let AudioStatus::Loaded(fmod_web) = &audio
+            && let Err(e) = handle_audio(fmod_web.as_ref(), &mut events, i)
+        {
+            console_log(&format!("Audio error: {:?}", e));
+        }
     }));
 
     request_animation_frame(g.borrow().as_ref().unwrap());
     Ok(())
 }
 
+enum AudioStatus {
+    Loading(Box<dyn AudioBackendLoader>),
+    Loaded(Box<dyn AudioBackend>),
+    Failed,
+}
+
 struct LoadedEvents {
     explosion_description: Box<dyn AudioEventDescription>,
 }
 
 fn handle_audio(
-    fmod_web: Box<dyn AudioBackend>,
+    fmod_web: &dyn AudioBackend,
     events: &mut Option<LoadedEvents>,
     i: i32,
 ) -> AudioResult<()> {